@@ -0,0 +1,97 @@
+//! Decimal / `BTreeMap` book against the fixed-point book, parsing and applying pushed levels.
+//!
+//! `cargo bench --bench book --features orderbook`
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use okx_rs::api::v5::Side;
+use okx_rs::book::fixed::{FixedBook, FixedScale};
+use okx_rs::book::OrderBook;
+use rust_decimal::Decimal;
+
+const LEVELS: usize = 400;
+const UPDATES: usize = 200_000;
+const ROUNDS: usize = 5;
+
+/// `(price, size, side)` updates around a mid of 42000.0, a fifth of them removing levels
+fn updates() -> Vec<(String, String, Side)> {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    (0..UPDATES)
+        .map(|_| {
+            let offset = next() % LEVELS as u64;
+            let side = if next() % 2 == 0 {
+                Side::Buy
+            } else {
+                Side::Sell
+            };
+            let ticks = match side {
+                Side::Buy => 420_000 - offset,
+                Side::Sell => 420_001 + offset,
+            };
+            let price = format!("{}.{}", ticks / 10, ticks % 10);
+            let size = if next() % 5 == 0 {
+                "0".to_string()
+            } else {
+                format!("{}.{:04}", next() % 10, next() % 10_000)
+            };
+            (price, size, side)
+        })
+        .collect()
+}
+
+fn bench(name: &str, mut run: impl FnMut()) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        run();
+        best = best.min(start.elapsed());
+    }
+    println!(
+        "{name:<28} {:>8.1} ns/update",
+        best.as_nanos() as f64 / UPDATES as f64
+    );
+}
+
+fn main() {
+    let updates = updates();
+
+    bench("decimal parse", || {
+        for (price, size, _) in &updates {
+            black_box(price.parse::<Decimal>().unwrap());
+            black_box(size.parse::<Decimal>().unwrap());
+        }
+    });
+    let price_scale = FixedScale::new("0.1").unwrap();
+    let size_scale = FixedScale::new("0.0001").unwrap();
+    bench("fixed parse", || {
+        for (price, size, _) in &updates {
+            black_box(price_scale.parse(price).unwrap());
+            black_box(size_scale.parse(size).unwrap());
+        }
+    });
+
+    bench("decimal btreemap apply", || {
+        let mut book = OrderBook::default();
+        for (price, size, side) in &updates {
+            book.handle_level(price.parse().unwrap(), size.parse().unwrap(), *side, false);
+        }
+        black_box(book.best_bid());
+    });
+    bench("fixed ladder apply", || {
+        let mut book = FixedBook::default();
+        for (price, size, side) in &updates {
+            book.handle_level(
+                price_scale.parse(price).unwrap(),
+                size_scale.parse(size).unwrap(),
+                *side,
+            );
+        }
+        black_box(book.best_bid());
+    });
+}
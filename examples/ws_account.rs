@@ -2,7 +2,7 @@ use log::info;
 use okx_rs::api::{DemoTrading, OKXEnv};
 use tungstenite::Message;
 
-use okx_rs::api::v5::ws_convert::TryParseEvent;
+use okx_rs::api::v5::ws_convert::{ChannelEvent, ControlEvent, WsMessage};
 use okx_rs::api::v5::{
     AccountChannel, BalanceAndPositionChannel, InstrumentType, PositionsChannel,
 };
@@ -19,13 +19,18 @@ fn main() {
     let passphrase = std::env::var("OKX_API_PASSPHRASE").unwrap();
     let options = Options::new_with(DemoTrading, key, secret, passphrase);
 
-    let (mut client, response) = tungstenite::connect(DemoTrading.private_websocket()).unwrap();
+    let (mut client, _response) = tungstenite::connect(DemoTrading.private_websocket()).unwrap();
 
     let auth_msg = OKXAuth::ws_auth(options).unwrap();
     client.send(auth_msg.into()).unwrap();
 
-    let auth_resp = client.read().unwrap();
-    info!("auth_resp: {:?}", auth_resp);
+    let auth_resp = client.read().unwrap().into_text().unwrap();
+    match WsMessage::try_parse(&auth_resp) {
+        Ok(Some(WsMessage::Control(ControlEvent::LoginOk { conn_id }))) => {
+            info!("logged in: {:?}", conn_id)
+        }
+        other => panic!("login failed: {:?}", other),
+    }
 
     client
         .send(AccountChannel.subscribe_message().into())
@@ -54,14 +59,14 @@ fn main() {
             _ => continue,
         };
 
-        if let Ok(Some(bal_and_pos)) = BalanceAndPositionChannel::try_parse(&msg) {
-            info!("{:?}", bal_and_pos);
-        } else if let Ok(Some(account)) = AccountChannel::try_parse(&msg) {
-            info!("{:?}", account);
-        } else if let Ok(Some(pos)) = PositionsChannel::try_parse(&msg) {
-            info!("{:?}", pos);
-        } else {
-            continue;
+        match ChannelEvent::try_parse(&msg) {
+            Ok(Some(ChannelEvent::BalanceAndPosition(bal_and_pos))) => {
+                info!("{:?}", bal_and_pos);
+            }
+            Ok(Some(ChannelEvent::Account(account))) => info!("{:?}", account),
+            Ok(Some(ChannelEvent::Positions(pos))) => info!("{:?}", pos),
+            Ok(_) => continue,
+            Err(err) => panic!("{:?}", err),
         }
     }
 }
@@ -2,7 +2,7 @@ use okx_rs::api::v5::ws_convert::TryParseEvent;
 use okx_rs::api::OKXEnv;
 use okx_rs::api::Production;
 use okx_rs::websocket::conn::Books5;
-use okx_rs::websocket::WebsocketChannel;
+use okx_rs::websocket::subscription::Subscriptions;
 
 fn main() {
     let (mut client, response) = tungstenite::connect(Production.public_websocket()).unwrap();
@@ -13,11 +13,14 @@ fn main() {
 
     let symbols = vec!["BTC-USDT-SWAP", "BTC-USDT", "ETH-USDT-SWAP", "ETH-USDT"];
 
-    for symbol in symbols {
-        let channel = Books5 {
+    let subscriptions: Subscriptions = symbols
+        .into_iter()
+        .map(|symbol| Books5 {
             inst_id: symbol.into(),
-        };
-        client.send(channel.subscribe_message().into()).unwrap();
+        })
+        .collect();
+    for message in subscriptions.subscribe_messages() {
+        client.send(message.into()).unwrap();
     }
 
     loop {
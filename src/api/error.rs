@@ -22,6 +22,35 @@ pub enum Error<T: Debug> {
 
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "simd")]
+    #[error(transparent)]
+    SimdJson(#[from] simd_json::Error),
+
+    #[error("websocket request timed out")]
+    Timeout,
+
+    #[error("websocket connection closed")]
+    ConnectionClosed,
+
+    #[cfg(feature = "websocket")]
+    #[error("websocket op {op} is served on {expected:?}, not {actual:?}")]
+    WrongEndpoint {
+        op: &'static str,
+        expected: crate::websocket::Endpoint,
+        actual: crate::websocket::Endpoint,
+    },
+
+    #[cfg(feature = "websocket")]
+    #[error(transparent)]
+    Websocket(Box<tungstenite::Error>),
+}
+
+#[cfg(feature = "websocket")]
+impl<T: Debug> From<tungstenite::Error> for Error<T> {
+    fn from(err: tungstenite::Error) -> Self {
+        Error::Websocket(Box::new(err))
+    }
 }
 
 #[derive(Debug, Error)]
@@ -1,6 +1,7 @@
-use crate::api::v5::model::{DepositAddress, DepositHistory};
+use crate::api::v5::model::{ChannelArg, DepositAddress, DepositHistory};
 use crate::api::v5::Request;
 use crate::serde_util::*;
+use crate::websocket::{Endpoint, WebsocketChannel};
 use reqwest::Method;
 use serde::Serialize;
 
@@ -85,3 +86,25 @@ impl Request for GetDepositAddress {
     const AUTH: bool = true;
     type Response = Vec<DepositAddress>;
 }
+
+/// https://www.okx.com/docs-v5/en/#funding-account-websocket-deposit-info-channel
+/// DepositInfoChannel(Ccy), all currencies when `None`. Served on the business endpoint
+pub struct DepositInfoChannel(pub Option<String>);
+
+impl WebsocketChannel for DepositInfoChannel {
+    const CHANNEL: &'static str = "deposit-info";
+    const AUTH: bool = true;
+    const ENDPOINT: Endpoint = Endpoint::Business;
+    type Response<'de> = Vec<DepositHistory>;
+    type ArgType<'de> = ChannelArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        let mut arg = serde_json::json!({
+            "channel": Self::CHANNEL,
+        });
+        if let Some(ccy) = &self.0 {
+            arg["ccy"] = ccy.as_str().into();
+        }
+        arg
+    }
+}
@@ -1,9 +1,11 @@
-use crate::api::v5::Request;
+use crate::api::v5::{ChannelArg, Request};
 use crate::impl_string_enum;
 use crate::serde_util::{deserialize_from_opt_str, str_opt, MaybeFloat};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
+use crate::websocket::{Endpoint, WebsocketChannel};
+
 #[derive(Debug, Clone)]
 pub enum WithdrawalStatus {
     PendingCancel,
@@ -87,6 +89,7 @@ pub struct WithdrawalHistory {
 ///
 /// > *The API can only make withdrawal to verified addresses, and verified addresses can be set by WEB/APP.*
 /// > *About tag: Some token deposits require a deposit address and a tag (e.g. Memo/Payment ID), which is a string that guarantees the uniqueness of your deposit address. Follow the deposit procedure carefully, or you may risk losing your assets.*
+///
 /// For currencies with labels, if it is a withdrawal between OKX users, please use internal transfer instead of online withdrawal
 /// Rate Limit: 6 requests per second
 /// Rate limit rule: UserID
@@ -240,3 +243,25 @@ impl Request for WithdrawalRequest {
 
     type Response = Vec<WithdrawalResponse>;
 }
+
+/// https://www.okx.com/docs-v5/en/#funding-account-websocket-withdrawal-info-channel
+/// WithdrawalInfoChannel(Ccy), all currencies when `None`. Served on the business endpoint
+pub struct WithdrawalInfoChannel(pub Option<String>);
+
+impl WebsocketChannel for WithdrawalInfoChannel {
+    const CHANNEL: &'static str = "withdrawal-info";
+    const AUTH: bool = true;
+    const ENDPOINT: Endpoint = Endpoint::Business;
+    type Response<'de> = Vec<WithdrawalHistory>;
+    type ArgType<'de> = ChannelArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        let mut arg = serde_json::json!({
+            "channel": Self::CHANNEL,
+        });
+        if let Some(ccy) = &self.0 {
+            arg["ccy"] = ccy.as_str().into();
+        }
+        arg
+    }
+}
@@ -11,6 +11,7 @@ pub mod funding_account;
 pub mod model;
 pub mod orderbook_trading;
 pub mod public_data;
+pub mod status;
 #[cfg(feature = "example")]
 pub mod testkit;
 pub mod trading_account;
@@ -27,6 +28,10 @@ pub use self::trading_account::websocket::*;
 // re-export public data module
 pub use self::public_data::rest::*;
 pub use self::public_data::websocket::*;
+// re-export status module
+pub use self::status::find_maintenance;
+pub use self::status::rest::*;
+pub use self::status::websocket::*;
 // re-export trading module
 pub use self::orderbook_trading::fill::*;
 pub use self::orderbook_trading::market_data::*;
@@ -61,6 +61,51 @@ impl_string_enum!(OrderType,
     Ioc => "ioc",
     OptimalLimitIoc => "optimal_limit_ioc",
 );
+impl_string_enum!(AlgoOrderType,
+    Other,
+    Conditional => "conditional",
+    Oco => "oco",
+    Trigger => "trigger",
+    MoveOrderStop => "move_order_stop",
+    Iceberg => "iceberg",
+    Twap => "twap",
+);
+impl_string_enum!(AlgoOrderState,
+    Other,
+    Live => "live",
+    Pause => "pause",
+    PartiallyEffective => "partially_effective",
+    Effective => "effective",
+    Canceled => "canceled",
+    OrderFailed => "order_failed",
+    PartiallyFailed => "partially_failed",
+);
+impl_string_enum!(MaintenanceState,
+    Other,
+    Scheduled => "scheduled",
+    Ongoing => "ongoing",
+    PreOpen => "pre_open",
+    Completed => "completed",
+    Canceled => "canceled",
+);
+impl_string_enum!(ServiceType,
+    Other,
+    Websocket => "0",
+    Trading => "5",
+    BlockTrading => "6",
+    TradingBot => "7",
+    TradingByAccounts => "8",
+    TradingByProducts => "9",
+    SpreadTrading => "10",
+    CopyTrading => "11",
+    Others => "99",
+);
+impl_string_enum!(MaintenanceType,
+    Other,
+    Scheduled => "1",
+    Unscheduled => "2",
+    System => "3",
+);
 impl_string_enum!(QuantityType,
     Other,
     BaseCcy => "base_ccy",
@@ -204,6 +249,66 @@ pub enum OrderType {
     OptimalLimitIoc,
 }
 
+#[derive(Debug, Clone, Copy, Hash)]
+pub enum AlgoOrderType {
+    Conditional,
+    Oco,
+    Trigger,
+    MoveOrderStop,
+    Iceberg,
+    Twap,
+    Other(Unknown),
+}
+
+#[derive(Debug, Clone, Copy, Hash)]
+pub enum AlgoOrderState {
+    Live,
+    Pause,
+    PartiallyEffective,
+    Effective,
+    Canceled,
+    OrderFailed,
+    PartiallyFailed,
+    Other(Unknown),
+}
+
+#[derive(Debug, Clone, Copy, Hash)]
+pub enum MaintenanceState {
+    Scheduled,
+    Ongoing,
+    /// Trading resumes with post-only orders before the window ends
+    PreOpen,
+    Completed,
+    Canceled,
+    Other(Unknown),
+}
+
+#[derive(Debug, Clone, Copy, Hash)]
+pub enum ServiceType {
+    Websocket,
+    /// Classic account trading service
+    Trading,
+    BlockTrading,
+    TradingBot,
+    /// Trading service upgraded in batches of accounts
+    TradingByAccounts,
+    /// Trading service upgraded in batches of products
+    TradingByProducts,
+    SpreadTrading,
+    CopyTrading,
+    Others,
+    Other(Unknown),
+}
+
+#[derive(Debug, Clone, Copy, Hash)]
+pub enum MaintenanceType {
+    Scheduled,
+    Unscheduled,
+    /// System maintenance
+    System,
+    Other(Unknown),
+}
+
 #[derive(Debug, Clone, Copy, Hash)]
 pub enum QuantityType {
     BaseCcy,
@@ -996,6 +1101,113 @@ pub struct OKXSystemTime {
     pub ts: u64,
 }
 
+/// System maintenance window, returned by `GetSystemStatus` and pushed on the `status` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatus {
+    /// The title of system maintenance instructions
+    pub title: String,
+    /// System maintenance status
+    #[serde(default, with = "str_opt")]
+    pub state: Option<MaintenanceState>,
+    /// Begin time of system maintenance, Unix timestamp format in milliseconds, e.g. 1617788463867
+    #[serde(default, with = "str_opt")]
+    pub begin: MaybeU64,
+    /// End time of system maintenance, Unix timestamp format in milliseconds, e.g. 1617788463867.
+    /// Before maintenance completes it is the expected end time, after it is the actual end time.
+    #[serde(default, with = "str_opt")]
+    pub end: MaybeU64,
+    /// Pre-open begin time, trading allows only post-only orders from then until `end`
+    #[serde(default, with = "str_opt")]
+    pub pre_open_begin: MaybeU64,
+    /// Hyperlink for system maintenance details
+    #[serde(default, with = "str_opt")]
+    pub href: Option<String>,
+    /// Service type
+    #[serde(default, with = "str_opt")]
+    pub service_type: Option<ServiceType>,
+    /// System, `unified` for trading account
+    #[serde(default, with = "str_opt")]
+    pub system: Option<String>,
+    /// Rescheduled description, e.g. `Rescheduled from 2021-01-26T16:30:00.000Z to 2021-01-28T16:30:00.000Z`
+    #[serde(default, with = "str_opt")]
+    pub sche_desc: Option<String>,
+    /// Maintenance type
+    #[serde(default, with = "str_opt")]
+    pub maint_type: Option<MaintenanceType>,
+    /// Environment, `1` production trading, `2` demo trading
+    #[serde(default, with = "str_opt")]
+    pub env: Option<String>,
+}
+
+impl SystemStatus {
+    /// Whether the window covers `ts` (Unix timestamp in milliseconds).
+    ///
+    /// `ongoing` and `pre_open` windows are always active, `completed` and `canceled` never are.
+    /// Other states are active between `begin` and `end`, a missing `end` leaves the window open.
+    pub fn is_active_at(&self, ts: u64) -> bool {
+        match &self.state {
+            Some(MaintenanceState::Ongoing | MaintenanceState::PreOpen) => true,
+            Some(MaintenanceState::Completed | MaintenanceState::Canceled) => false,
+            _ => self.begin.is_some_and(|begin| begin <= ts) && self.end.is_none_or(|end| ts < end),
+        }
+    }
+
+    /// Whether the window affects order book trading of `inst_type`.
+    ///
+    /// Block trading, trading bot, spread trading and copy trading windows affect no instrument type,
+    /// every other window affects all of them. OKX does not list affected products, so only windows
+    /// upgrading the trading service in batches of products (`serviceType` 9) are restricted to the
+    /// products named as whole words in their title or description, e.g. "Spot and margin".
+    pub fn affects(&self, inst_type: InstrumentType) -> bool {
+        match &self.service_type {
+            Some(
+                ServiceType::BlockTrading
+                | ServiceType::TradingBot
+                | ServiceType::SpreadTrading
+                | ServiceType::CopyTrading,
+            ) => false,
+            Some(ServiceType::TradingByProducts) => {
+                let named = self.named_products();
+                named.is_empty() || inst_type == InstrumentType::Any || named.contains(&inst_type)
+            }
+            _ => true,
+        }
+    }
+
+    /// Instrument types named as whole words in the title and description.
+    /// "Portfolio margin" is an account mode, not margin trading
+    fn named_products(&self) -> Vec<InstrumentType> {
+        let text = format!(
+            "{} {}",
+            self.title,
+            self.sche_desc.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        let mut named = Vec::new();
+        let mut previous = "";
+        for word in text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            let inst_type = match word {
+                "spot" => Some(InstrumentType::Spot),
+                "margin" if previous != "portfolio" => Some(InstrumentType::Margin),
+                "swap" | "swaps" | "perpetual" | "perpetuals" => Some(InstrumentType::Swap),
+                "futures" | "expiry" => Some(InstrumentType::Futures),
+                "option" | "options" => Some(InstrumentType::Option),
+                _ => None,
+            };
+            previous = word;
+            match inst_type {
+                Some(inst_type) if !named.contains(&inst_type) => named.push(inst_type),
+                _ => {}
+            }
+        }
+        named
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscountInfo {
@@ -1146,6 +1358,13 @@ pub struct Candle {
     /// Close price
     #[serde(rename = "c")]
     pub close: f64,
+    /// Trading volume in contracts (derivatives) or base currency (spot).
+    /// Only present on trading candles, not on mark price and index candles
+    pub vol: Option<f64>,
+    /// Trading volume in base currency (derivatives) or quote currency (spot)
+    pub vol_ccy: Option<f64>,
+    /// Trading volume in quote currency
+    pub vol_ccy_quote: Option<f64>,
     /// The state of candlesticks.
     /// 0 represents that it is uncompleted, 1 represents that it is completed.
     pub confirm: CandleState,
@@ -1170,7 +1389,7 @@ pub enum Levels<'a> {
 
 #[allow(clippy::len_without_is_empty)]
 impl<'a> Levels<'a> {
-    pub fn iter(&self) -> impl Iterator<Item = &Level> + '_ {
+    pub fn iter(&self) -> impl Iterator<Item = &Level<'a>> + '_ {
         match self {
             Levels::Depth1(s) => s.iter(),
             Levels::Depth5(s) => s.iter(),
@@ -1260,13 +1479,16 @@ impl<'de> Deserialize<'de> for Level<'de> {
 }
 
 /// Custom deserializer for candlestick
-/// expecting candle format: [ts, open, high, low, close, confirm]
+/// expecting candle format: [ts, open, high, low, close, confirm] for mark price and index candles,
+/// or [ts, open, high, low, close, vol, volCcy, volCcyQuote, confirm] for trading candles
 struct CandleVisitor;
 impl<'de> Visitor<'de> for CandleVisitor {
     type Value = Candle;
 
     fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
-        formatter.write_str("candle of format: [ts, open, high, low, close, confirm]")
+        formatter.write_str(
+            "candle of format: [ts, open, high, low, close, (vol, volCcy, volCcyQuote,) confirm]",
+        )
     }
 
     fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
@@ -1281,7 +1503,7 @@ impl<'de> Visitor<'de> for CandleVisitor {
             seq.next_element::<&str>()?,
             seq.next_element::<&str>()?,
         ) {
-            (Some(ts), Some(open), Some(high), Some(low), Some(close), Some(confirm)) => {
+            (Some(ts), Some(open), Some(high), Some(low), Some(close), Some(sixth)) => {
                 let ts =
                     u64::from_str(ts).map_err(|_| S::Error::custom("unknown timestamp format"))?;
                 let open =
@@ -1291,6 +1513,23 @@ impl<'de> Visitor<'de> for CandleVisitor {
                 let low = f64::from_str(low).map_err(|_| S::Error::custom("unknown low format"))?;
                 let close =
                     f64::from_str(close).map_err(|_| S::Error::custom("unknown close format"))?;
+                let volume = |vol: &str| {
+                    f64::from_str(vol).map_err(|_| S::Error::custom("unknown volume format"))
+                };
+                let (vol, vol_ccy, vol_ccy_quote, confirm) = match (
+                    seq.next_element::<&str>()?,
+                    seq.next_element::<&str>()?,
+                    seq.next_element::<&str>()?,
+                ) {
+                    (None, ..) => (None, None, None, sixth),
+                    (Some(vol_ccy), Some(vol_ccy_quote), Some(confirm)) => (
+                        Some(volume(sixth)?),
+                        Some(volume(vol_ccy)?),
+                        Some(volume(vol_ccy_quote)?),
+                        confirm,
+                    ),
+                    _ => return Err(serde::de::Error::custom("invalid candle format")),
+                };
                 let confirm = CandleState::from_str(confirm)
                     .map_err(|_| S::Error::custom(format!("unknown candle state: {}", confirm)))?;
                 Ok(Candle {
@@ -1299,6 +1538,9 @@ impl<'de> Visitor<'de> for CandleVisitor {
                     high,
                     low,
                     close,
+                    vol,
+                    vol_ccy,
+                    vol_ccy_quote,
                     confirm,
                 })
             }
@@ -1331,7 +1573,575 @@ mod tests_parse_candle {
         assert_eq!(candle.low, 3.677);
         assert_eq!(candle.close, 3.708);
         assert_eq!(candle.confirm, super::CandleState::Uncompleted);
+        assert_eq!(candle.vol, None);
     }
+
+    #[test]
+    fn test_deser_trading_candle() {
+        let json = r#"["1597026383085","8533.02","8553.74","8527.17","8548.26","45247","529.5858061","5063", "1"]"#;
+        let candle: Candle = serde_json::from_str(json).unwrap();
+        assert_eq!(candle.close, 8548.26);
+        assert_eq!(candle.vol, Some(45247.0));
+        assert_eq!(candle.vol_ccy, Some(529.5858061));
+        assert_eq!(candle.vol_ccy_quote, Some(5063.0));
+        assert_eq!(candle.confirm, super::CandleState::Completed);
+
+        let json = r#"["1597026383085","8533.02","8553.74","8527.17","8548.26","45247","1"]"#;
+        assert!(serde_json::from_str::<Candle>(json).is_err());
+    }
+}
+
+/// Push data of the `tickers` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker<'a> {
+    /// Instrument type
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USDT
+    pub inst_id: &'a str,
+    /// Last traded price
+    #[serde(default, with = "str_opt")]
+    pub last: MaybeFloat,
+    /// Last traded size
+    #[serde(default, with = "str_opt")]
+    pub last_sz: MaybeFloat,
+    /// Best ask price
+    #[serde(default, with = "str_opt")]
+    pub ask_px: MaybeFloat,
+    /// Best ask size
+    #[serde(default, with = "str_opt")]
+    pub ask_sz: MaybeFloat,
+    /// Best bid price
+    #[serde(default, with = "str_opt")]
+    pub bid_px: MaybeFloat,
+    /// Best bid size
+    #[serde(default, with = "str_opt")]
+    pub bid_sz: MaybeFloat,
+    /// Open price in the past 24 hours
+    #[serde(default, with = "str_opt")]
+    pub open_24h: MaybeFloat,
+    /// Highest price in the past 24 hours
+    #[serde(default, with = "str_opt")]
+    pub high_24h: MaybeFloat,
+    /// Lowest price in the past 24 hours
+    #[serde(default, with = "str_opt")]
+    pub low_24h: MaybeFloat,
+    /// 24h trading volume, with a unit of currency.
+    /// If it is a derivatives contract, the value is the number of base currency.
+    /// If it is SPOT/MARGIN, the value is the quantity in quote currency.
+    #[serde(default, with = "str_opt")]
+    pub vol_ccy_24h: MaybeFloat,
+    /// 24h trading volume, with a unit of contract.
+    /// If it is a derivatives contract, the value is the number of contracts.
+    /// If it is SPOT/MARGIN, the value is the quantity in base currency.
+    #[serde(default, with = "str_opt")]
+    pub vol_24h: MaybeFloat,
+    /// Open price in the UTC 0
+    #[serde(default, with = "str_opt")]
+    pub sod_utc0: MaybeFloat,
+    /// Open price in the UTC 8
+    #[serde(default, with = "str_opt")]
+    pub sod_utc8: MaybeFloat,
+    /// Ticker data generation time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `trades` and `trades-all` channels
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trade<'a> {
+    /// Instrument ID, e.g. BTC-USDT
+    pub inst_id: &'a str,
+    /// Trade ID
+    pub trade_id: &'a str,
+    /// Trade price
+    #[serde(default, with = "str_opt")]
+    pub px: MaybeFloat,
+    /// Trade size
+    #[serde(default, with = "str_opt")]
+    pub sz: MaybeFloat,
+    /// Trade direction of the taker
+    #[serde(default, with = "str_opt")]
+    pub side: Option<Side>,
+    /// The count of trades aggregated, only pushed on `trades`
+    #[serde(default, with = "str_opt")]
+    pub count: MaybeU64,
+    /// Filled time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `funding-rate` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateRef<'a> {
+    /// Instrument type, SWAP
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USD-SWAP
+    pub inst_id: &'a str,
+    /// Funding rate mechanism, `current_period` or `next_period`
+    #[serde(default)]
+    pub method: Option<&'a str>,
+    /// Current funding rate
+    #[serde(default, with = "str_opt")]
+    pub funding_rate: MaybeFloat,
+    /// Settlement time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub funding_time: MaybeU64,
+    /// Forecasted funding rate for the next period
+    #[serde(default, with = "str_opt")]
+    pub next_funding_rate: MaybeFloat,
+    /// Forecasted funding time for the next period, Unix timestamp format in milliseconds
+    #[serde(default, with = "str_opt")]
+    pub next_funding_time: MaybeU64,
+    /// The lower limit of the predicted funding rate of the next cycle
+    #[serde(default, with = "str_opt")]
+    pub min_funding_rate: MaybeFloat,
+    /// The upper limit of the predicted funding rate of the next cycle
+    #[serde(default, with = "str_opt")]
+    pub max_funding_rate: MaybeFloat,
+    /// If settState = processing, it is the funding rate that is being used for current settlement cycle.
+    /// If settState = settled, it is the funding rate that is being used for previous settlement cycle
+    #[serde(default, with = "str_opt")]
+    pub sett_funding_rate: MaybeFloat,
+    /// Settlement state of funding rate, `processing` or `settled`
+    #[serde(default)]
+    pub sett_state: Option<&'a str>,
+    /// Premium between the mid price of perps market and the index price
+    #[serde(default, with = "str_opt")]
+    pub premium: MaybeFloat,
+    /// Data return time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `open-interest` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestRef<'a> {
+    /// Instrument type
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USD-SWAP
+    pub inst_id: &'a str,
+    /// Open interest, in units of contracts
+    #[serde(default, with = "str_opt")]
+    pub oi: MaybeFloat,
+    /// Open interest, in currency units, like BTC
+    #[serde(default, with = "str_opt")]
+    pub oi_ccy: MaybeFloat,
+    /// Open interest, in number of USD
+    #[serde(default, with = "str_opt")]
+    pub oi_usd: MaybeFloat,
+    /// Data return time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `price-limit` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceLimitRef<'a> {
+    /// Instrument ID, e.g. BTC-USDT
+    pub inst_id: &'a str,
+    /// Maximum buy price, empty when `enabled` is false
+    #[serde(default, with = "str_opt")]
+    pub buy_lmt: MaybeFloat,
+    /// Minimum sell price, empty when `enabled` is false
+    #[serde(default, with = "str_opt")]
+    pub sell_lmt: MaybeFloat,
+    /// Whether price limit is effective
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Price update time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `liquidation-orders` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationOrder<'a> {
+    /// Instrument type
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USD-SWAP
+    pub inst_id: &'a str,
+    /// Instrument family, e.g. BTC-USD
+    #[serde(default)]
+    pub inst_family: Option<&'a str>,
+    /// Underlying, e.g. BTC-USD
+    #[serde(default)]
+    pub uly: Option<&'a str>,
+    /// Liquidation details
+    #[serde(borrow)]
+    pub details: Vec<LiquidationOrderDetail<'a>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationOrderDetail<'a> {
+    /// Order side of the liquidation order
+    #[serde(default, with = "str_opt")]
+    pub side: Option<Side>,
+    /// Position side
+    #[serde(default, with = "str_opt")]
+    pub pos_side: Option<PositionSide>,
+    /// Bankruptcy price
+    #[serde(default, with = "str_opt")]
+    pub bk_px: MaybeFloat,
+    /// Quantity of liquidation, in contracts for derivatives
+    #[serde(default, with = "str_opt")]
+    pub sz: MaybeFloat,
+    /// Bankruptcy loss
+    #[serde(default, with = "str_opt")]
+    pub bk_loss: MaybeFloat,
+    /// Liquidation currency, only applicable to MARGIN
+    #[serde(default)]
+    pub ccy: Option<&'a str>,
+    /// Liquidation time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `estimated-price` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimatedPrice<'a> {
+    /// Instrument type, FUTURES or OPTION
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USD-170310
+    pub inst_id: &'a str,
+    /// Type, `settlement` or `delivery` / `exercise`
+    #[serde(default)]
+    pub settle_type: Option<&'a str>,
+    /// Estimated delivery / exercise / settlement price
+    #[serde(default, with = "str_opt")]
+    pub settle_px: MaybeFloat,
+    /// Data return time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `opt-summary` channel, greeks and implied volatilities of an option
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptSummary<'a> {
+    /// Instrument type, OPTION
+    pub inst_type: InstrumentType,
+    /// Instrument ID, e.g. BTC-USD-200103-5500-C
+    pub inst_id: &'a str,
+    /// Underlying, e.g. BTC-USD
+    #[serde(default)]
+    pub uly: Option<&'a str>,
+    /// Sensitivity of option price to `uly` price, in coin
+    #[serde(default, with = "str_opt")]
+    pub delta: MaybeFloat,
+    /// The delta is sensitivity to `uly` price, in coin
+    #[serde(default, with = "str_opt")]
+    pub gamma: MaybeFloat,
+    /// Sensitivity of option price to implied volatility, in coin
+    #[serde(default, with = "str_opt")]
+    pub vega: MaybeFloat,
+    /// Sensitivity of option price to remaining maturity, in coin
+    #[serde(default, with = "str_opt")]
+    pub theta: MaybeFloat,
+    /// Sensitivity of option price to `uly` price, Black-Scholes model in dollars
+    #[serde(rename = "deltaBS", default, with = "str_opt")]
+    pub delta_bs: MaybeFloat,
+    /// The delta is sensitivity to `uly` price, Black-Scholes model in dollars
+    #[serde(rename = "gammaBS", default, with = "str_opt")]
+    pub gamma_bs: MaybeFloat,
+    /// Sensitivity of option price to remaining maturity, Black-Scholes model in dollars
+    #[serde(rename = "thetaBS", default, with = "str_opt")]
+    pub theta_bs: MaybeFloat,
+    /// Sensitivity of option price to implied volatility, Black-Scholes model in dollars
+    #[serde(rename = "vegaBS", default, with = "str_opt")]
+    pub vega_bs: MaybeFloat,
+    /// Leverage
+    #[serde(default, with = "str_opt")]
+    pub lever: MaybeFloat,
+    /// Mark volatility
+    #[serde(default, with = "str_opt")]
+    pub mark_vol: MaybeFloat,
+    /// Bid volatility
+    #[serde(default, with = "str_opt")]
+    pub bid_vol: MaybeFloat,
+    /// Ask volatility
+    #[serde(default, with = "str_opt")]
+    pub ask_vol: MaybeFloat,
+    /// Realized volatility (not currently used)
+    #[serde(default, with = "str_opt")]
+    pub real_vol: MaybeFloat,
+    /// Implied volatility of at-the-money options
+    #[serde(default, with = "str_opt")]
+    pub vol_lv: MaybeFloat,
+    /// Forward price
+    #[serde(default, with = "str_opt")]
+    pub fwd_px: MaybeFloat,
+    /// Data update time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `option-trades` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionTrade<'a> {
+    /// Instrument ID, e.g. BTC-USD-221230-4000-C
+    pub inst_id: &'a str,
+    /// Instrument family, e.g. BTC-USD
+    #[serde(default)]
+    pub inst_family: Option<&'a str>,
+    /// Trade ID
+    pub trade_id: &'a str,
+    /// Trade price
+    #[serde(default, with = "str_opt")]
+    pub px: MaybeFloat,
+    /// Trade quantity, in contracts
+    #[serde(default, with = "str_opt")]
+    pub sz: MaybeFloat,
+    /// Trade direction of the taker
+    #[serde(default, with = "str_opt")]
+    pub side: Option<Side>,
+    /// Option type, C: Call P: Put
+    #[serde(rename = "optType", default, with = "str_opt")]
+    pub option_type: Option<OptionType>,
+    /// Implied volatility while trading
+    #[serde(default, with = "str_opt")]
+    pub fill_vol: MaybeFloat,
+    /// Forward price while trading
+    #[serde(default, with = "str_opt")]
+    pub fwd_px: MaybeFloat,
+    /// Index price while trading
+    #[serde(default, with = "str_opt")]
+    pub idx_px: MaybeFloat,
+    /// Mark price while trading
+    #[serde(default, with = "str_opt")]
+    pub mark_px: MaybeFloat,
+    /// Trade time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+/// Push data of the `orders-algo` channel, conditional, oco and trigger orders
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoOrder<'a> {
+    /// Instrument type
+    pub inst_type: InstrumentType,
+    /// Instrument ID
+    pub inst_id: &'a str,
+    /// Margin currency, only applicable to cross MARGIN orders in Single-currency margin
+    #[serde(default)]
+    pub ccy: Option<&'a str>,
+    /// Latest order ID, the order ID associated with the algo order
+    #[serde(default)]
+    pub ord_id: Option<&'a str>,
+    /// Algo ID
+    pub algo_id: &'a str,
+    /// Client Order ID as assigned by the client
+    #[serde(default)]
+    pub cl_ord_id: Option<&'a str>,
+    /// Client-supplied Algo ID
+    #[serde(default)]
+    pub algo_cl_ord_id: Option<&'a str>,
+    /// Quantity to buy or sell
+    #[serde(default, with = "str_opt")]
+    pub sz: MaybeFloat,
+    /// Order type
+    #[serde(default, with = "str_opt")]
+    pub ord_type: Option<AlgoOrderType>,
+    /// Order side
+    #[serde(default, with = "str_opt")]
+    pub side: Option<Side>,
+    /// Position side
+    #[serde(default, with = "str_opt")]
+    pub pos_side: Option<PositionSide>,
+    /// Trade mode
+    #[serde(default, with = "str_opt")]
+    pub td_mode: Option<TradeMode>,
+    /// Order quantity unit setting for sz
+    #[serde(default, with = "str_opt")]
+    pub tgt_ccy: Option<QuantityType>,
+    /// Order status
+    #[serde(default, with = "str_opt")]
+    pub state: Option<AlgoOrderState>,
+    /// Leverage, from 0.01 to 125. Only applicable to MARGIN/FUTURES/SWAP
+    #[serde(default, with = "str_opt")]
+    pub lever: MaybeFloat,
+    /// Take-profit trigger price
+    #[serde(default, with = "str_opt")]
+    pub tp_trigger_px: MaybeFloat,
+    /// Take-profit trigger price type
+    #[serde(default, with = "str_opt")]
+    pub tp_trigger_px_type: Option<TakeProfitTriggerPriceType>,
+    /// Take-profit order price
+    #[serde(default, with = "str_opt")]
+    pub tp_ord_px: MaybeFloat,
+    /// Stop-loss trigger price
+    #[serde(default, with = "str_opt")]
+    pub sl_trigger_px: MaybeFloat,
+    /// Stop-loss trigger price type
+    #[serde(default, with = "str_opt")]
+    pub sl_trigger_px_type: Option<StopLossTriggerPriceType>,
+    /// Stop-loss order price
+    #[serde(default, with = "str_opt")]
+    pub sl_ord_px: MaybeFloat,
+    /// Trigger price
+    #[serde(default, with = "str_opt")]
+    pub trigger_px: MaybeFloat,
+    /// Order price for the trigger order, -1 for market
+    #[serde(default, with = "str_opt")]
+    pub ord_px: MaybeFloat,
+    /// Actual order quantity
+    #[serde(default, with = "str_opt")]
+    pub actual_sz: MaybeFloat,
+    /// Actual order price
+    #[serde(default, with = "str_opt")]
+    pub actual_px: MaybeFloat,
+    /// Actual trigger side, `tp`, `sl` or empty
+    #[serde(default)]
+    pub actual_side: Option<&'a str>,
+    /// Trigger time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub trigger_time: MaybeU64,
+    /// Whether the order can only reduce the position size
+    #[serde(default, with = "str_opt")]
+    pub reduce_only: Option<bool>,
+    /// Order tag
+    #[serde(default)]
+    pub tag: Option<&'a str>,
+    /// Error code when the algo order fails to trigger, empty otherwise
+    #[serde(default)]
+    pub fail_code: Option<&'a str>,
+    /// Creation time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub c_time: MaybeU64,
+    /// Order updated time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub u_time: MaybeU64,
+}
+
+/// Push data of the `algo-advance` channel, iceberg, twap and trailing stop orders
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoAdvance<'a> {
+    /// Instrument type
+    pub inst_type: InstrumentType,
+    /// Instrument ID
+    pub inst_id: &'a str,
+    /// Margin currency, only applicable to cross MARGIN orders in Single-currency margin
+    #[serde(default)]
+    pub ccy: Option<&'a str>,
+    /// Algo ID
+    pub algo_id: &'a str,
+    /// Client-supplied Algo ID
+    #[serde(default)]
+    pub algo_cl_ord_id: Option<&'a str>,
+    /// Quantity to buy or sell
+    #[serde(default, with = "str_opt")]
+    pub sz: MaybeFloat,
+    /// Order type
+    #[serde(default, with = "str_opt")]
+    pub ord_type: Option<AlgoOrderType>,
+    /// Order side
+    #[serde(default, with = "str_opt")]
+    pub side: Option<Side>,
+    /// Position side
+    #[serde(default, with = "str_opt")]
+    pub pos_side: Option<PositionSide>,
+    /// Trade mode
+    #[serde(default, with = "str_opt")]
+    pub td_mode: Option<TradeMode>,
+    /// Order quantity unit setting for sz
+    #[serde(default, with = "str_opt")]
+    pub tgt_ccy: Option<QuantityType>,
+    /// Order status
+    #[serde(default, with = "str_opt")]
+    pub state: Option<AlgoOrderState>,
+    /// Leverage, from 0.01 to 125. Only applicable to MARGIN/FUTURES/SWAP
+    #[serde(default, with = "str_opt")]
+    pub lever: MaybeFloat,
+    /// Algo order quantity that has been filled
+    #[serde(default, with = "str_opt")]
+    pub actual_sz: MaybeFloat,
+    /// Price ratio, only applicable to iceberg and twap orders
+    #[serde(default, with = "str_opt")]
+    pub px_var: MaybeFloat,
+    /// Price variance, only applicable to iceberg and twap orders
+    #[serde(default, with = "str_opt")]
+    pub px_spread: MaybeFloat,
+    /// Average amount, only applicable to iceberg and twap orders
+    #[serde(default, with = "str_opt")]
+    pub sz_limit: MaybeFloat,
+    /// Price limit, only applicable to iceberg and twap orders
+    #[serde(default, with = "str_opt")]
+    pub px_limit: MaybeFloat,
+    /// Time interval in seconds, only applicable to twap orders
+    #[serde(default, with = "str_opt")]
+    pub time_interval: MaybeU64,
+    /// Callback price ratio, only applicable to move_order_stop orders
+    #[serde(default, with = "str_opt")]
+    pub callback_ratio: MaybeFloat,
+    /// Callback price variance, only applicable to move_order_stop orders
+    #[serde(default, with = "str_opt")]
+    pub callback_spread: MaybeFloat,
+    /// Active price, only applicable to move_order_stop orders
+    #[serde(default, with = "str_opt")]
+    pub active_px: MaybeFloat,
+    /// Trigger price, only applicable to move_order_stop orders
+    #[serde(default, with = "str_opt")]
+    pub move_trigger_px: MaybeFloat,
+    /// Whether the order can only reduce the position size
+    #[serde(default, with = "str_opt")]
+    pub reduce_only: Option<bool>,
+    /// Trigger time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub trigger_time: MaybeU64,
+    /// Order tag
+    #[serde(default)]
+    pub tag: Option<&'a str>,
+    /// Creation time, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub c_time: MaybeU64,
+    /// Push time of algo order information, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub p_time: MaybeU64,
+}
+
+/// Push data of the `account-greeks` channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountGreeks {
+    /// Currency
+    pub ccy: String,
+    /// delta: Black-Scholes Greeks in dollars
+    #[serde(rename = "deltaBS", default, with = "str_opt")]
+    pub delta_bs: MaybeFloat,
+    /// delta: Greeks in coins
+    #[serde(rename = "deltaPA", default, with = "str_opt")]
+    pub delta_pa: MaybeFloat,
+    /// gamma: Black-Scholes Greeks in dollars, only applicable to OPTION
+    #[serde(rename = "gammaBS", default, with = "str_opt")]
+    pub gamma_bs: MaybeFloat,
+    /// gamma: Greeks in coins, only applicable to OPTION
+    #[serde(rename = "gammaPA", default, with = "str_opt")]
+    pub gamma_pa: MaybeFloat,
+    /// theta: Black-Scholes Greeks in dollars, only applicable to OPTION
+    #[serde(rename = "thetaBS", default, with = "str_opt")]
+    pub theta_bs: MaybeFloat,
+    /// theta: Greeks in coins, only applicable to OPTION
+    #[serde(rename = "thetaPA", default, with = "str_opt")]
+    pub theta_pa: MaybeFloat,
+    /// vega: Black-Scholes Greeks in dollars, only applicable to OPTION
+    #[serde(rename = "vegaBS", default, with = "str_opt")]
+    pub vega_bs: MaybeFloat,
+    /// vega: Greeks in coins, only applicable to OPTION
+    #[serde(rename = "vegaPA", default, with = "str_opt")]
+    pub vega_pa: MaybeFloat,
+    /// Time of getting Greeks, Unix timestamp format in milliseconds, e.g. 1597026383085
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1339,6 +2149,17 @@ pub struct ChannelArg<'a> {
     pub channel: &'a str,
 }
 
+/// Argument of channels subscribed by instrument family, e.g. `opt-summary`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstFamilyArg<'a> {
+    pub channel: &'a str,
+    #[serde(default)]
+    pub inst_family: Option<&'a str>,
+    #[serde(default)]
+    pub inst_id: Option<&'a str>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Currency {
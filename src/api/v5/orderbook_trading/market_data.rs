@@ -1,6 +1,9 @@
 use crate::api::v5::model::Side;
-use crate::api::v5::{IndexTicker, Request};
+use crate::api::v5::{
+    ChannelArg, IndexTicker, InstFamilyArg, InstrumentType, OptionTrade, Request, Ticker, Trade,
+};
 use crate::serde_util::*;
+use crate::websocket::{Endpoint, WebsocketChannel};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
@@ -98,3 +101,105 @@ impl Request for GetTrades {
     const PATH: &'static str = "/market/history-trades";
     type Response = Vec<TradeHistory>;
 }
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-order-book
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOrderBook {
+    pub inst_id: String,
+    /// Depth per side, 1 by default and at most 400
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sz: Option<u32>,
+}
+
+/// Order book returned by [`GetOrderBook`], levels are `[price, size, "0", orders]`
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderBookSnapshot {
+    pub asks: Vec<[String; 4]>,
+    pub bids: Vec<[String; 4]>,
+    #[serde(default, with = "str_opt")]
+    pub ts: MaybeU64,
+}
+
+impl Request for GetOrderBook {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/market/books";
+    type Response = Vec<OrderBookSnapshot>;
+}
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-tickers-channel
+/// Tickers(InstId)
+pub struct Tickers(pub String);
+impl WebsocketChannel for Tickers {
+    const CHANNEL: &'static str = "tickers";
+    type Response<'de> = Vec<Ticker<'de>>;
+    type ArgType<'de> = ChannelArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        serde_json::json!({
+            "channel": Self::CHANNEL,
+            "instId": self.0,
+        })
+    }
+}
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-trades-channel
+/// Trades(InstId), trades aggregated by taker order
+pub struct Trades(pub String);
+impl WebsocketChannel for Trades {
+    const CHANNEL: &'static str = "trades";
+    type Response<'de> = Vec<Trade<'de>>;
+    type ArgType<'de> = ChannelArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        serde_json::json!({
+            "channel": Self::CHANNEL,
+            "instId": self.0,
+        })
+    }
+}
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-all-trades-channel
+/// TradesAll(InstId), every trade without aggregation, served on the business endpoint
+pub struct TradesAll(pub String);
+impl WebsocketChannel for TradesAll {
+    const CHANNEL: &'static str = "trades-all";
+    const ENDPOINT: Endpoint = Endpoint::Business;
+    type Response<'de> = Vec<Trade<'de>>;
+    type ArgType<'de> = ChannelArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        serde_json::json!({
+            "channel": Self::CHANNEL,
+            "instId": self.0,
+        })
+    }
+}
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-option-trades-channel
+/// Either `inst_family` or `inst_id` is required, `inst_id` takes precedence
+pub struct OptionTrades {
+    /// Instrument family, e.g. BTC-USD
+    pub inst_family: Option<String>,
+    /// Instrument ID, e.g. BTC-USD-221230-4000-C
+    pub inst_id: Option<String>,
+}
+impl WebsocketChannel for OptionTrades {
+    const CHANNEL: &'static str = "option-trades";
+    type Response<'de> = Vec<OptionTrade<'de>>;
+    type ArgType<'de> = InstFamilyArg<'de>;
+
+    fn subscribe_arg(&self) -> serde_json::Value {
+        let mut arg = serde_json::json!({
+            "channel": Self::CHANNEL,
+            "instType": InstrumentType::Option,
+        });
+        if let Some(inst_family) = &self.inst_family {
+            arg["instFamily"] = inst_family.as_str().into();
+        }
+        if let Some(inst_id) = &self.inst_id {
+            arg["instId"] = inst_id.as_str().into();
+        }
+        arg
+    }
+}
@@ -200,6 +200,75 @@ impl Request for PlaceOrder {
     type Response = Vec<PlaceOrderResponse>;
 }
 
+/// https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-amend-order
+/// Either ordId or clOrdId is required, if both are passed, ordId will be used.
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendOrder {
+    /// Instrument ID
+    pub inst_id: String,
+    /// Whether the order needs to be automatically canceled when the order amendment fails
+    /// Valid options: false or true, the default is false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cxl_on_fail: Option<bool>,
+    /// Order ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ord_id: Option<String>,
+    /// Client Order ID as assigned by the client
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
+    /// Client Request ID as assigned by the client for order amendment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<String>,
+    /// New quantity after amendment. Either newSz or newPx is required.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_sz: Option<String>,
+    /// New price after amendment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_px: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendOrderResponse {
+    /// Order ID
+    #[serde(default, deserialize_with = "deserialize_from_opt_str")]
+    pub ord_id: MaybeString,
+    /// Client Order ID as assigned by the client
+    #[serde(default, deserialize_with = "deserialize_from_opt_str")]
+    pub cl_ord_id: MaybeString,
+    /// Client Request ID as assigned by the client for order amendment
+    #[serde(default, deserialize_with = "deserialize_from_opt_str")]
+    pub req_id: MaybeString,
+    /// The code of the event execution result, 0 means success.
+    #[serde(default, deserialize_with = "deserialize_from_opt_str")]
+    pub s_code: MaybeU64,
+    /// Rejection or success message of event execution.
+    #[serde(default, deserialize_with = "deserialize_from_opt_str")]
+    pub s_msg: MaybeString,
+}
+
+/// https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-mass-cancel-order
+/// Cancel all the MMP pending orders of an instrument family.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MassCancel {
+    /// Instrument type, OPTION
+    pub inst_type: InstrumentType,
+    /// Instrument family
+    pub inst_family: String,
+    /// Lock interval(ms). The range should be [0, 10 000]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_interval: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MassCancelResponse {
+    /// Result of the request true, false
+    pub result: bool,
+}
+
 /// https://www.okx.com/docs-v5/en/#rest-api-trade-get-order-details
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -407,7 +476,58 @@ impl Request for GetOrderList {
 
 pub mod websocket {
     use super::*;
-    use crate::websocket::WebsocketChannel;
+    use crate::api::v5::{AlgoAdvance, AlgoOrder};
+    use crate::websocket::{Endpoint, WebsocketChannel, WebsocketOp};
+
+    macro_rules! impl_websocket_op {
+        (Vec<$request:ty>, $op:literal, $response:ty) => {
+            impl WebsocketOp for Vec<$request> {
+                const OP: &'static str = $op;
+                type Arg = $request;
+                type Response = Vec<$response>;
+
+                fn args(&self) -> &[Self::Arg] {
+                    self
+                }
+            }
+        };
+        ($request:ty, $op:literal, $response:ty) => {
+            impl_websocket_op!($request, $op, $response, Endpoint::Private);
+        };
+        ($request:ty, $op:literal, $response:ty, $endpoint:expr) => {
+            impl WebsocketOp for $request {
+                const OP: &'static str = $op;
+                const ENDPOINT: Endpoint = $endpoint;
+                type Arg = $request;
+                type Response = Vec<$response>;
+
+                fn args(&self) -> &[Self::Arg] {
+                    std::slice::from_ref(self)
+                }
+            }
+        };
+    }
+
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-place-order
+    impl_websocket_op!(PlaceOrder, "order", PlaceOrderResponse);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-place-multiple-orders
+    impl_websocket_op!(Vec<PlaceOrder>, "batch-orders", PlaceOrderResponse);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-cancel-order
+    impl_websocket_op!(CancelOrder, "cancel-order", CancelOrderData);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-cancel-multiple-orders
+    impl_websocket_op!(Vec<CancelOrder>, "batch-cancel-orders", CancelOrderData);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-amend-order
+    impl_websocket_op!(AmendOrder, "amend-order", AmendOrderResponse);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-amend-multiple-orders
+    impl_websocket_op!(Vec<AmendOrder>, "batch-amend-orders", AmendOrderResponse);
+    // https://www.okx.com/docs-v5/en/#order-book-trading-trade-ws-mass-cancel-order
+    // only accepted on the business endpoint
+    impl_websocket_op!(
+        MassCancel,
+        "mass-cancel",
+        MassCancelResponse,
+        Endpoint::Business
+    );
 
     #[derive(Debug, Deserialize)]
     #[serde(rename_all = "camelCase")]
@@ -423,25 +543,101 @@ pub mod websocket {
         type Response<'de> = [OrderDetailRef<'de>; 1];
         type ArgType<'de> = OrdersChannelArg<'de>;
 
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
             let OrdersChannel(inst_type) = self;
             serde_json::json!({
-                "op": "subscribe",
-                "args": [
-                    {
-                        "channel": Self::CHANNEL,
-                        "instType": inst_type,
-                    }
-                ]
+                "channel": Self::CHANNEL,
+                "instType": inst_type,
             })
-            .to_string()
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#order-book-trading-algo-trading-ws-algo-orders-channel
+    /// Conditional, oco and trigger orders, served on the business endpoint
+    pub struct AlgoOrdersChannel {
+        /// SPOT, MARGIN, SWAP, FUTURES or ANY
+        pub inst_type: InstrumentType,
+        /// Instrument family, applicable to FUTURES/SWAP/OPTION
+        pub inst_family: Option<String>,
+        pub inst_id: Option<String>,
+    }
+    impl WebsocketChannel for AlgoOrdersChannel {
+        const CHANNEL: &'static str = "orders-algo";
+        const AUTH: bool = true;
+        const ENDPOINT: Endpoint = Endpoint::Business;
+        type Response<'de> = Vec<AlgoOrder<'de>>;
+        type ArgType<'de> = OrdersChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            let mut arg = serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instType": self.inst_type,
+            });
+            if let Some(inst_family) = &self.inst_family {
+                arg["instFamily"] = inst_family.as_str().into();
+            }
+            if let Some(inst_id) = &self.inst_id {
+                arg["instId"] = inst_id.as_str().into();
+            }
+            arg
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#order-book-trading-algo-trading-ws-advance-algo-orders-channel
+    /// Iceberg, twap and trailing stop orders, served on the business endpoint
+    pub struct AlgoAdvanceChannel {
+        /// SPOT, MARGIN, SWAP, FUTURES or ANY
+        pub inst_type: InstrumentType,
+        pub inst_id: Option<String>,
+        pub algo_id: Option<String>,
+    }
+    impl WebsocketChannel for AlgoAdvanceChannel {
+        const CHANNEL: &'static str = "algo-advance";
+        const AUTH: bool = true;
+        const ENDPOINT: Endpoint = Endpoint::Business;
+        type Response<'de> = Vec<AlgoAdvance<'de>>;
+        type ArgType<'de> = OrdersChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            let mut arg = serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instType": self.inst_type,
+            });
+            if let Some(inst_id) = &self.inst_id {
+                arg["instId"] = inst_id.as_str().into();
+            }
+            if let Some(algo_id) = &self.algo_id {
+                arg["algoId"] = algo_id.as_str().into();
+            }
+            arg
         }
     }
 }
 
-pub struct OrderOp;
-impl WebsocketChannel for OrderOp {
-    const CHANNEL: &'static str = "";
-    type Response<'de> = [PlaceOrderResponse; 1];
-    type ArgType<'de> = ();
+macro_rules! impl_op_channel {
+    ($name:ident, $op:literal, $response:ty) => {
+        pub struct $name;
+        impl WebsocketChannel for $name {
+            /// op name of the request / response. `op` responses carry no `arg.channel`
+            const CHANNEL: &'static str = $op;
+            type Response<'de> = $response;
+            type ArgType<'de> = ();
+        }
+    };
 }
+
+impl_op_channel!(OrderOp, "order", [PlaceOrderResponse; 1]);
+impl_op_channel!(BatchOrdersOp, "batch-orders", Vec<PlaceOrderResponse>);
+impl_op_channel!(CancelOrderOp, "cancel-order", [CancelOrderData; 1]);
+impl_op_channel!(
+    BatchCancelOrdersOp,
+    "batch-cancel-orders",
+    Vec<CancelOrderData>
+);
+impl_op_channel!(AmendOrderOp, "amend-order", [AmendOrderResponse; 1]);
+impl_op_channel!(
+    BatchAmendOrdersOp,
+    "batch-amend-orders",
+    Vec<AmendOrderResponse>
+);
+impl_op_channel!(MassCancelOp, "mass-cancel", [MassCancelResponse; 1]);
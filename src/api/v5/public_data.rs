@@ -4,9 +4,10 @@ use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 use crate::api::v5::model::{
-    Candle, DeliveryExerciseHistory, DiscountRateAndInterestFreeQuota, FundingRateHistory,
-    IndexTicker, Instrument, InstrumentType, InsuranceFund, OKXSystemTime, OpenInterest,
-    PositionTier, PriceLimit,
+    Candle, DeliveryExerciseHistory, DiscountRateAndInterestFreeQuota, EstimatedPrice,
+    FundingRateHistory, FundingRateRef, IndexTicker, InstFamilyArg, Instrument, InstrumentType,
+    InsuranceFund, LiquidationOrder, OKXSystemTime, OpenInterest, OpenInterestRef, OptSummary,
+    PositionTier, PriceLimit, PriceLimitRef,
 };
 
 pub mod rest {
@@ -651,7 +652,7 @@ pub mod rest {
 pub mod websocket {
     use super::*;
     use crate::api::v5::ChannelArg;
-    use crate::websocket::WebsocketChannel;
+    use crate::websocket::{Endpoint, WebsocketChannel};
 
     pub struct Instruments(pub InstrumentType);
     impl WebsocketChannel for Instruments {
@@ -659,21 +660,11 @@ pub mod websocket {
         type Response<'de> = Vec<Instrument>;
         type ArgType<'de> = ChannelArg<'de>;
 
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
             serde_json::json!({
-                "op": "subscribe",
-                "args": [
-                    {
-                      "channel": Self::CHANNEL,
-                      "instType": self.0,
-                    }
-                ]
+                "channel": Self::CHANNEL,
+                "instType": self.0,
             })
-            .to_string()
-        }
-
-        fn unsubscribe_message(&self) -> String {
-            todo!()
         }
     }
 
@@ -684,21 +675,11 @@ pub mod websocket {
         type Response<'de> = Vec<MarkPrice>;
         type ArgType<'de> = ChannelArg<'de>;
 
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
             serde_json::json!({
-                "op": "subscribe",
-                "args": [
-                    {
-                      "channel": Self::CHANNEL,
-                      "instId": self.0,
-                    }
-                ]
+                "channel": Self::CHANNEL,
+                "instId": self.0,
             })
-            .to_string()
-        }
-
-        fn unsubscribe_message(&self) -> String {
-            todo!()
         }
     }
 
@@ -709,21 +690,180 @@ pub mod websocket {
         type Response<'de> = Vec<IndexTicker>;
         type ArgType<'de> = ChannelArg<'de>;
 
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
+            serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instId": self.0,
+            })
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-funding-rate-channel
+    /// FundingRates(InstId)
+    pub struct FundingRates(pub String);
+    impl WebsocketChannel for FundingRates {
+        const CHANNEL: &'static str = "funding-rate";
+        type Response<'de> = Vec<FundingRateRef<'de>>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instId": self.0,
+            })
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-open-interest-channel
+    /// OpenInterests(InstId)
+    pub struct OpenInterests(pub String);
+    impl WebsocketChannel for OpenInterests {
+        const CHANNEL: &'static str = "open-interest";
+        type Response<'de> = Vec<OpenInterestRef<'de>>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instId": self.0,
+            })
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-price-limit-channel
+    /// PriceLimits(InstId)
+    pub struct PriceLimits(pub String);
+    impl WebsocketChannel for PriceLimits {
+        const CHANNEL: &'static str = "price-limit";
+        type Response<'de> = Vec<PriceLimitRef<'de>>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instId": self.0,
+            })
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-liquidation-orders-channel
+    /// LiquidationOrders(InstType), SWAP, FUTURES, MARGIN or OPTION
+    pub struct LiquidationOrders(pub InstrumentType);
+    impl WebsocketChannel for LiquidationOrders {
+        const CHANNEL: &'static str = "liquidation-orders";
+        type Response<'de> = Vec<LiquidationOrder<'de>>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
             serde_json::json!({
-                "op": "subscribe",
-                "args": [
-                    {
-                      "channel": "index-tickers",
-                      "instId": self.0,
-                    }
-                ]
+                "channel": Self::CHANNEL,
+                "instType": self.0,
             })
-            .to_string()
         }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-estimated-delivery-exercise-settlement-price-channel
+    /// Either `inst_family` or `inst_id` is required
+    pub struct EstimatedPrices {
+        /// FUTURES or OPTION
+        pub inst_type: InstrumentType,
+        /// Instrument family, e.g. BTC-USD
+        pub inst_family: Option<String>,
+        /// Instrument ID, e.g. BTC-USD-170310
+        pub inst_id: Option<String>,
+    }
+    impl WebsocketChannel for EstimatedPrices {
+        const CHANNEL: &'static str = "estimated-price";
+        type Response<'de> = Vec<EstimatedPrice<'de>>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            let mut arg = serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instType": self.inst_type,
+            });
+            if let Some(inst_family) = &self.inst_family {
+                arg["instFamily"] = inst_family.as_str().into();
+            }
+            if let Some(inst_id) = &self.inst_id {
+                arg["instId"] = inst_id.as_str().into();
+            }
+            arg
+        }
+    }
 
-        fn unsubscribe_message(&self) -> String {
-            todo!()
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-option-summary-channel
+    /// OptSummaries(InstFamily), pushes the greeks of every option of the instrument family
+    pub struct OptSummaries(pub String);
+    impl WebsocketChannel for OptSummaries {
+        const CHANNEL: &'static str = "opt-summary";
+        type Response<'de> = Vec<OptSummary<'de>>;
+        type ArgType<'de> = InstFamilyArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            serde_json::json!({
+                "channel": Self::CHANNEL,
+                "instFamily": self.0,
+            })
         }
     }
+
+    /// Candle channels are named after their bar, e.g. `candle1m`, `mark-price-candle1H`
+    fn is_candle_channel(channel: &str, prefix: &str) -> bool {
+        channel
+            .strip_prefix(prefix)
+            .is_some_and(|bar| bar.starts_with(|c: char| c.is_ascii_digit()))
+    }
+
+    macro_rules! impl_candle_channel {
+        ($channel:ident, $name:literal) => {
+            impl WebsocketChannel for $channel {
+                const CHANNEL: &'static str = $name;
+                const ENDPOINT: Endpoint = Endpoint::Business;
+                type Response<'de> = Vec<Candle>;
+                type ArgType<'de> = ChannelArg<'de>;
+
+                fn subscribe_arg(&self) -> serde_json::Value {
+                    serde_json::json!({
+                        "channel": format!("{}{}", Self::CHANNEL, self.bar),
+                        "instId": self.inst_id,
+                    })
+                }
+
+                fn matches_channel(channel: &str) -> bool {
+                    is_candle_channel(channel, Self::CHANNEL)
+                }
+            }
+        };
+    }
+
+    /// https://www.okx.com/docs-v5/en/#order-book-trading-market-data-ws-candlesticks-channel
+    /// Candlesticks of an instrument, served on the business endpoint
+    pub struct Candles {
+        /// Bar size, e.g. [1m/3m/5m/15m/30m/1H/2H/4H/6Hutc/12Hutc/1Dutc/1Wutc/1Mutc]
+        pub bar: String,
+        /// Instrument ID, e.g. BTC-USDT
+        pub inst_id: String,
+    }
+    impl_candle_channel!(Candles, "candle");
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-mark-price-candlesticks-channel
+    /// Candlesticks of the mark price, served on the business endpoint
+    pub struct MarkPriceCandles {
+        /// Bar size, e.g. [1m/3m/5m/15m/30m/1H/2H/4H/6Hutc/12Hutc/1Dutc/1Wutc/1Mutc]
+        pub bar: String,
+        /// Instrument ID, e.g. BTC-USD-SWAP
+        pub inst_id: String,
+    }
+    impl_candle_channel!(MarkPriceCandles, "mark-price-candle");
+
+    /// https://www.okx.com/docs-v5/en/#public-data-websocket-index-candlesticks-channel
+    /// Candlesticks of an index, served on the business endpoint
+    pub struct IndexCandles {
+        /// Bar size, e.g. [1m/3m/5m/15m/30m/1H/2H/4H/6Hutc/12Hutc/1Dutc/1Wutc/1Mutc]
+        pub bar: String,
+        /// Index, e.g. BTC-USD
+        pub inst_id: String,
+    }
+    impl_candle_channel!(IndexCandles, "index-candle");
 }
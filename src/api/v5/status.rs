@@ -0,0 +1,150 @@
+use crate::api::v5::model::{ChannelArg, InstrumentType, MaintenanceState, SystemStatus};
+use crate::api::v5::Request;
+use crate::websocket::WebsocketChannel;
+use reqwest::Method;
+use serde::Serialize;
+
+pub mod rest {
+    use super::*;
+
+    /// https://www.okx.com/docs-v5/en/#status-get-status
+    /// ## Status
+    /// Get event status of system upgrade.
+    ///
+    /// Planned system maintenance that may result in short interruption (lasting less than 5 seconds)
+    /// or websocket disconnection (users can immediately reconnect) will not be announced.
+    ///
+    /// Rate Limit: 1 request per 5 seconds
+    /// ## HTTP Request
+    /// GET /api/v5/system/status
+    #[derive(Debug, Clone, Serialize, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GetSystemStatus {
+        /// System maintenance status, `scheduled`, `ongoing`, `pre_open`, `completed` or `canceled`.
+        /// Defaults to `scheduled`, `ongoing` and `pre_open`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub state: Option<MaintenanceState>,
+    }
+
+    impl Request for GetSystemStatus {
+        const METHOD: Method = Method::GET;
+        const PATH: &'static str = "/system/status";
+        const AUTH: bool = false;
+
+        type Response = Vec<SystemStatus>;
+    }
+}
+
+pub mod websocket {
+    use super::*;
+
+    /// https://www.okx.com/docs-v5/en/#status-ws-status-channel
+    /// Pushes scheduled maintenance windows on subscription and whenever they change
+    pub struct StatusChannel;
+    impl WebsocketChannel for StatusChannel {
+        const CHANNEL: &'static str = "status";
+        type Response<'de> = Vec<SystemStatus>;
+        type ArgType<'de> = ChannelArg<'de>;
+    }
+}
+
+/// Returns the first window in `statuses` active at `ts` (Unix timestamp in milliseconds) that affects `inst_type`.
+///
+/// Trading pauses during such a window and order books restart with a fresh snapshot
+/// once it ends, so callers can stop quoting and expect a sequence reset.
+pub fn find_maintenance(
+    statuses: &[SystemStatus],
+    inst_type: InstrumentType,
+    ts: u64,
+) -> Option<&SystemStatus> {
+    statuses
+        .iter()
+        .find(|status| status.is_active_at(ts) && status.affects(inst_type))
+}
+
+#[cfg(test)]
+mod tests_system_status {
+    use super::find_maintenance;
+    use crate::api::v5::{InstrumentType, MaintenanceState, ServiceType, SystemStatus};
+
+    const STATUS: &str = r#"[
+        {"begin":"1672823400000","end":"1672823520000","href":"","preOpenBegin":"","scheDesc":"","serviceType":"0","state":"completed","maintType":"1","env":"1","system":"unified","title":"Trading account WebSocket system upgrade"},
+        {"begin":"1672826400000","end":"1672830000000","href":"","preOpenBegin":"","scheDesc":"","serviceType":"9","state":"scheduled","maintType":"1","env":"1","system":"unified","title":"Spot and margin system upgrade"},
+        {"begin":"1672826400000","end":"1672830000000","href":"","preOpenBegin":"","scheDesc":"","serviceType":"7","state":"scheduled","maintType":"1","env":"1","system":"unified","title":"Trading bot upgrade"}
+    ]"#;
+
+    #[test]
+    fn test_deser_system_status() {
+        let statuses: Vec<SystemStatus> = serde_json::from_str(STATUS).unwrap();
+        assert!(matches!(
+            statuses[0].state,
+            Some(MaintenanceState::Completed)
+        ));
+        assert!(matches!(
+            statuses[1].service_type,
+            Some(ServiceType::TradingByProducts)
+        ));
+        assert_eq!(statuses[1].begin, Some(1672826400000));
+        assert_eq!(statuses[1].pre_open_begin, None);
+    }
+
+    #[test]
+    fn test_find_maintenance() {
+        let statuses: Vec<SystemStatus> = serde_json::from_str(STATUS).unwrap();
+        // completed window is never active
+        assert!(find_maintenance(&statuses, InstrumentType::Spot, 1672823500000).is_none());
+
+        let during = 1672828000000;
+        let status = find_maintenance(&statuses, InstrumentType::Spot, during).unwrap();
+        assert_eq!(status.title, "Spot and margin system upgrade");
+        assert!(find_maintenance(&statuses, InstrumentType::Margin, during).is_some());
+        assert!(find_maintenance(&statuses, InstrumentType::Swap, during).is_none());
+        assert!(find_maintenance(&statuses, InstrumentType::Spot, 1672830000000).is_none());
+
+        // a window naming no product covers them all
+        let mut statuses = statuses;
+        statuses[1].title = "Trading system upgrade".into();
+        assert!(find_maintenance(&statuses, InstrumentType::Option, during).is_some());
+        statuses[1].state = Some(MaintenanceState::Ongoing);
+        assert!(find_maintenance(&statuses, InstrumentType::Option, 0).is_some());
+    }
+
+    #[test]
+    fn test_affects_misleading_titles() {
+        let status = |service_type: &str, title: &str| {
+            let json = format!(
+                r#"{{"begin":"1","end":"2","serviceType":"{service_type}","state":"ongoing","title":"{title}"}}"#
+            );
+            serde_json::from_str::<SystemStatus>(&json).unwrap()
+        };
+        let all = [
+            InstrumentType::Spot,
+            InstrumentType::Margin,
+            InstrumentType::Swap,
+            InstrumentType::Futures,
+            InstrumentType::Option,
+        ];
+
+        // only windows upgrading in batches of products are narrowed
+        let status_5 = status("5", "Portfolio margin upgrade");
+        assert!(all.iter().all(|inst_type| status_5.affects(*inst_type)));
+        let status_5 = status("5", "Spot system upgrade");
+        assert!(status_5.affects(InstrumentType::Swap));
+
+        // portfolio margin is an account mode, it names no product
+        let status_9 = status("9", "Portfolio margin upgrade");
+        assert!(all.iter().all(|inst_type| status_9.affects(*inst_type)));
+
+        // words are matched whole
+        let status_9 = status("9", "Spot upgrade, optional reconnect");
+        assert!(status_9.affects(InstrumentType::Spot));
+        assert!(!status_9.affects(InstrumentType::Option));
+        assert!(!status_9.affects(InstrumentType::Margin));
+        let status_9 = status("9", "Perpetual swaps and options upgrade");
+        assert!(status_9.affects(InstrumentType::Swap));
+        assert!(status_9.affects(InstrumentType::Option));
+        assert!(!status_9.affects(InstrumentType::Spot));
+
+        assert!(!status("7", "Trading bot upgrade").affects(InstrumentType::Spot));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::api::v5::model::{
-    InstrumentType, InterestAccrued, InterestLimitResponse, MarginMode, PositionDetail,
-    TradingBalanceDetail,
+    AccountGreeks, InstrumentType, InterestAccrued, InterestLimitResponse, MarginMode,
+    PositionDetail, TradingBalanceDetail,
 };
 use crate::api::v5::Request;
 use crate::websocket::WebsocketChannel;
@@ -139,21 +139,15 @@ pub mod websocket {
         const AUTH: bool = true;
         type Response<'de> = Vec<TradingBalanceDetail>;
         type ArgType<'de> = ChannelArg<'de>;
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
             json!({
-                "op": "subscribe",
-                "args": [
+                "channel": Self::CHANNEL,
+                "extraParams": "
                     {
-                        "channel": Self::CHANNEL,
-                        "extraParams": "
-                        {
-                          \"updateInterval\": \"1\"
-                        }
-                    "
+                      \"updateInterval\": \"1\"
                     }
-                ]
+                "
             })
-            .to_string()
         }
     }
 
@@ -200,19 +194,70 @@ pub mod websocket {
         type Response<'de> = Vec<PositionDetail>;
         type ArgType<'de> = PositionsArg<'de>;
 
-        fn subscribe_message(&self) -> String {
+        fn subscribe_arg(&self) -> serde_json::Value {
             json!({
-                "op": "subscribe",
-                "args": [
-                    {
-                        "channel": Self::CHANNEL,
-                        "instType": self.inst_type,
-                        "instId": self.inst_id,
-                        "instFamily": self.inst_family,
-                    }
-                ]
+                "channel": Self::CHANNEL,
+                "instType": self.inst_type,
+                "instId": self.inst_id,
+                "instFamily": self.inst_family,
             })
-            .to_string()
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#trading-account-websocket-position-risk-warning
+    /// Pushed before positions reach the liquidation threshold, with the same payload as `positions`
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct LiquidationWarningChannel {
+        /// MARGIN, SWAP, FUTURES, OPTION or ANY
+        pub inst_type: InstrumentType,
+        /// Instrument family
+        /// Applicable to FUTURES/SWAP/OPTION
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub inst_family: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub inst_id: Option<String>,
+    }
+
+    impl WebsocketChannel for LiquidationWarningChannel {
+        const CHANNEL: &'static str = "liquidation-warning";
+        const AUTH: bool = true;
+        type Response<'de> = Vec<PositionDetail>;
+        type ArgType<'de> = PositionsArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            let mut arg = json!({
+                "channel": Self::CHANNEL,
+                "instType": self.inst_type,
+            });
+            if let Some(inst_family) = &self.inst_family {
+                arg["instFamily"] = inst_family.as_str().into();
+            }
+            if let Some(inst_id) = &self.inst_id {
+                arg["instId"] = inst_id.as_str().into();
+            }
+            arg
+        }
+    }
+
+    /// https://www.okx.com/docs-v5/en/#trading-account-websocket-account-greeks-channel
+    /// AccountGreeksChannel(Ccy), all currencies when `None`
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct AccountGreeksChannel(pub Option<String>);
+
+    impl WebsocketChannel for AccountGreeksChannel {
+        const CHANNEL: &'static str = "account-greeks";
+        const AUTH: bool = true;
+        type Response<'de> = Vec<AccountGreeks>;
+        type ArgType<'de> = ChannelArg<'de>;
+
+        fn subscribe_arg(&self) -> serde_json::Value {
+            let mut arg = json!({
+                "channel": Self::CHANNEL,
+            });
+            if let Some(ccy) = &self.0 {
+                arg["ccy"] = ccy.as_str().into();
+            }
+            arg
         }
     }
 
@@ -1,6 +1,16 @@
 use crate::api::error::{ApiError, Error};
-use crate::api::v5::orderbook_trading::orders::websocket::OrdersChannel;
-use crate::api::v5::{AccountChannel, BalanceAndPositionChannel, OrderOp, PositionsChannel};
+use crate::api::v5::orderbook_trading::orders::websocket::{
+    AlgoAdvanceChannel, AlgoOrdersChannel, OrdersChannel,
+};
+use crate::api::v5::{
+    AccountChannel, AccountGreeksChannel, AmendOrderOp, BalanceAndPositionChannel,
+    BatchAmendOrdersOp, BatchCancelOrdersOp, BatchOrdersOp, CancelOrderOp, Candles,
+    DepositInfoChannel, EstimatedPrices, FundingRates, IndexCandles, IndexTickers,
+    LiquidationOrders, LiquidationWarningChannel, MarkPriceCandles, MassCancelOp, OpenInterests,
+    OptSummaries, OptionTrades, OrderOp, PositionsChannel, PriceLimits, StatusChannel, Tickers,
+    Trades, TradesAll, WithdrawalInfoChannel,
+};
+use crate::serde_util::str_opt;
 use crate::{
     api::v5::Instruments,
     api::v5::MarkPrices,
@@ -10,6 +20,7 @@ use crate::{
 };
 use const_format::concatcp;
 use serde::Deserialize;
+use std::borrow::Cow;
 use std::fmt::Debug;
 
 fn deser_from_str<'a, T>(s: &'a str) -> serde_json::Result<T>
@@ -27,6 +38,20 @@ where
     simd_json::from_slice(unsafe { s.as_bytes_mut() })
 }
 
+#[cfg(feature = "simd")]
+fn log_simd_parse_error(err: &simd_json::Error) {
+    // the input has been partially unescaped in place by simd-json, only the error is logged
+    log::error!("{:?}", err);
+}
+
+fn log_parse_error(msg: &str, err: &serde_json::Error) {
+    log::error!("{}", msg);
+    log::error!("{:?}", err);
+    let column = err.column().min(msg.len());
+    let highlight = &msg.as_bytes()[column.saturating_sub(20)..(column + 20).min(msg.len())];
+    log::error!(".. {:?} ..", String::from_utf8_lossy(highlight));
+}
+
 macro_rules! impl_channel_match {
     ($channel:ident) => {
         impl ChannelMatch for $channel {
@@ -37,6 +62,18 @@ macro_rules! impl_channel_match {
 }
 impl_channel_match!(Instruments);
 impl_channel_match!(MarkPrices);
+impl_channel_match!(IndexTickers);
+impl_channel_match!(Tickers);
+impl_channel_match!(Trades);
+impl_channel_match!(TradesAll);
+impl_channel_match!(FundingRates);
+impl_channel_match!(OpenInterests);
+impl_channel_match!(PriceLimits);
+impl_channel_match!(LiquidationOrders);
+impl_channel_match!(EstimatedPrices);
+impl_channel_match!(OptSummaries);
+impl_channel_match!(OptionTrades);
+impl_channel_match!(StatusChannel);
 impl_channel_match!(Books);
 impl_channel_match!(BooksL2Tbt);
 impl_channel_match!(Books5);
@@ -45,10 +82,39 @@ impl_channel_match!(PositionsChannel);
 impl_channel_match!(AccountChannel);
 impl_channel_match!(BalanceAndPositionChannel);
 impl_channel_match!(OrdersChannel);
+impl_channel_match!(AlgoOrdersChannel);
+impl_channel_match!(AlgoAdvanceChannel);
+impl_channel_match!(LiquidationWarningChannel);
+impl_channel_match!(AccountGreeksChannel);
+impl_channel_match!(DepositInfoChannel);
+impl_channel_match!(WithdrawalInfoChannel);
+
+// candle channels are named after their bar, e.g. `candle1m`, and match on the prefix
+macro_rules! impl_channel_prefix_match {
+    ($channel:ident) => {
+        impl ChannelMatch for $channel {
+            const CHANNEL_PATTERN: &'static str = concatcp!(r#""channel":""#, $channel::CHANNEL);
+        }
+    };
+}
+impl_channel_prefix_match!(Candles);
+impl_channel_prefix_match!(MarkPriceCandles);
+impl_channel_prefix_match!(IndexCandles);
 
-impl ChannelMatch for OrderOp {
-    const CHANNEL_PATTERN: &'static str = r#""op":"order""#;
+macro_rules! impl_op_match {
+    ($op:ident) => {
+        impl ChannelMatch for $op {
+            const CHANNEL_PATTERN: &'static str = concatcp!(r#""op":""#, $op::CHANNEL, r#"""#);
+        }
+    };
 }
+impl_op_match!(OrderOp);
+impl_op_match!(BatchOrdersOp);
+impl_op_match!(CancelOrderOp);
+impl_op_match!(BatchCancelOrdersOp);
+impl_op_match!(AmendOrderOp);
+impl_op_match!(BatchAmendOrdersOp);
+impl_op_match!(MassCancelOp);
 
 #[cfg(test)]
 mod test_channel_match {
@@ -72,6 +138,11 @@ pub trait TryParseEvent {
     type ErrorData: Debug;
 
     fn try_parse(msg: &str) -> Result<Option<Self::Value<'_>>, Error<Self::ErrorData>>;
+
+    /// Same as [`TryParseEvent::try_parse`], deserializing with simd-json.
+    /// `msg` is modified in place and must not be reused.
+    #[cfg(feature = "simd")]
+    fn try_parse_simd(msg: &mut str) -> Result<Option<Self::Value<'_>>, Error<Self::ErrorData>>;
 }
 impl<T> TryParseEvent for T
 where
@@ -84,94 +155,950 @@ where
 
     fn try_parse(msg: &str) -> Result<Option<Self::Value<'_>>, Error<Self::ErrorData>> {
         if msg.contains(T::CHANNEL_PATTERN) {
-            let response: Self::Value<'_> = match deser_from_str(msg) {
-                Ok(Some(res)) => res,
-                Ok(None) => return Ok(None),
+            match deser_from_str(msg) {
+                Ok(Some(response)) => filter_response(response),
+                Ok(None) => Ok(None),
                 Err(err) => {
-                    log::error!("{}", msg);
-                    log::error!("{:?}", err);
-                    let highlight = &msg.as_bytes()[err.column() - 20..err.column() + 20];
-                    log::error!(".. {:?} ..", String::from_utf8(highlight.to_vec()).unwrap());
-                    return Err(err.into());
+                    log_parse_error(msg, &err);
+                    Err(err.into())
                 }
-            };
-            if response.event == Some("error") {
-                log::error!("{:?}", response);
-                let WsResponse {
-                    code, conn_id, msg, ..
-                } = response;
-                return Err(Error::Api(ApiError {
-                    code,
-                    msg: msg.to_owned().map(str::to_string),
-                    data: Some(()),
-                    conn_id: conn_id.to_owned().map(str::to_string),
-                }));
-            } else if response.event == Some("subscribe") || response.event == Some("unsubscribe") {
-                log::info!("{:?}", response);
-                // TODO: propagate subscribe/unsubscribe event
-                return Ok(None);
-            }
-            Ok(Some(response))
+            }
         } else {
             Ok(None)
         }
     }
+
+    #[cfg(feature = "simd")]
+    fn try_parse_simd(msg: &mut str) -> Result<Option<Self::Value<'_>>, Error<Self::ErrorData>> {
+        if msg.contains(T::CHANNEL_PATTERN) {
+            match deser_from_str_simd(msg) {
+                Ok(Some(response)) => filter_response(response),
+                Ok(None) => Ok(None),
+                Err(err) => {
+                    log_simd_parse_error(&err);
+                    Err(err.into())
+                }
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Turns `event: error` responses into `Err` and drops subscribe acks
+fn filter_response<A: Debug, T: Debug>(
+    response: WsResponse<'_, A, T>,
+) -> Result<Option<WsResponse<'_, A, T>>, Error<()>> {
+    if response.event == Some("error") {
+        log::error!("{:?}", response);
+        let WsResponse {
+            code, conn_id, msg, ..
+        } = response;
+        return Err(Error::Api(ApiError {
+            code,
+            msg: msg.to_owned().map(str::to_string),
+            data: Some(()),
+            conn_id: conn_id.to_owned().map(str::to_string),
+        }));
+    } else if response.event == Some("subscribe") || response.event == Some("unsubscribe") {
+        // control events are surfaced by `WsMessage::try_parse`
+        log::info!("{:?}", response);
+        return Ok(None);
+    }
+    Ok(Some(response))
+}
+
+/// Parsed response of a websocket channel
+pub type ChannelResponse<'a, C> =
+    WsResponse<'a, <C as WebsocketChannel>::ArgType<'a>, <C as WebsocketChannel>::Response<'a>>;
+
+/// Routing fields of a websocket message. `data` is skipped without being deserialized.
+#[derive(Debug, Deserialize)]
+struct Route<'a> {
+    #[serde(borrow)]
+    op: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    event: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    arg: Option<RouteArg<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RouteArg<'a> {
+    #[serde(borrow)]
+    channel: Option<Cow<'a, str>>,
+}
+
+#[cfg(feature = "simd")]
+impl<'a> Route<'a> {
+    fn from_value(value: &simd_json::BorrowedValue<'a>) -> Self {
+        use simd_json::prelude::*;
+
+        fn string<'a>(value: Option<&simd_json::BorrowedValue<'a>>) -> Option<Cow<'a, str>> {
+            match value {
+                Some(simd_json::BorrowedValue::String(s)) => Some(s.clone()),
+                _ => None,
+            }
+        }
+        Route {
+            op: string(value.get("op")),
+            event: string(value.get("event")),
+            arg: value.get("arg").map(|arg| RouteArg {
+                channel: string(arg.get("channel")),
+            }),
+        }
+    }
+}
+
+/// Parsed message a [`ChannelEvent`] is deserialized from once its route is known
+trait MessageSource<'a> {
+    fn deserialize<T: Deserialize<'a>>(self) -> Result<T, Error<()>>;
+}
+
+impl<'a> MessageSource<'a> for &'a str {
+    fn deserialize<T: Deserialize<'a>>(self) -> Result<T, Error<()>> {
+        ChannelEvent::parse(self)
+    }
+}
+
+#[cfg(feature = "simd")]
+impl<'a> MessageSource<'a> for simd_json::BorrowedValue<'a> {
+    fn deserialize<T: Deserialize<'a>>(self) -> Result<T, Error<()>> {
+        simd_json::serde::from_borrowed_value(self).map_err(|err| {
+            log_simd_parse_error(&err);
+            err.into()
+        })
+    }
+}
+
+macro_rules! impl_channel_event {
+    (
+        channels: { $($variant:ident => $channel:ty,)+ }
+        ops: { $($op_variant:ident => $op:ty,)+ }
+    ) => {
+        /// Push data of every supported channel, routed by `arg.channel`,
+        /// and responses of websocket trading ops, routed by `op`
+        #[derive(Debug)]
+        pub enum ChannelEvent<'a> {
+            $(
+                $variant(ChannelResponse<'a, $channel>),
+            )+
+            $(
+                $op_variant(ChannelResponse<'a, $op>),
+            )+
+        }
+
+        impl<'a> ChannelEvent<'a> {
+            fn from_channel<S: MessageSource<'a>>(
+                channel: &str,
+                source: S,
+            ) -> Result<Option<Self>, Error<()>> {
+                $(
+                    if <$channel as WebsocketChannel>::matches_channel(channel) {
+                        return source.deserialize().map(|response| Some(Self::$variant(response)));
+                    }
+                )+
+                Ok(None)
+            }
+
+            fn from_op<S: MessageSource<'a>>(op: &str, source: S) -> Result<Option<Self>, Error<()>> {
+                $(
+                    if op == <$op as WebsocketChannel>::CHANNEL {
+                        return source.deserialize().map(|response| Some(Self::$op_variant(response)));
+                    }
+                )+
+                Ok(None)
+            }
+
+            /// Whether subscribing to `channel` requires login, see [`WebsocketChannel::AUTH`].
+            /// `false` for channels that are not routed
+            pub fn requires_login(channel: &str) -> bool {
+                $(
+                    if <$channel as WebsocketChannel>::matches_channel(channel) {
+                        return <$channel as WebsocketChannel>::AUTH;
+                    }
+                )+
+                false
+            }
+
+            /// Channel name, or op name for trading op responses.
+            /// Candle channels return the name without bar, e.g. `candle`
+            pub fn channel(&self) -> &'static str {
+                match self {
+                    $(
+                        Self::$variant(_) => <$channel as WebsocketChannel>::CHANNEL,
+                    )+
+                    $(
+                        Self::$op_variant(_) => <$op as WebsocketChannel>::CHANNEL,
+                    )+
+                }
+            }
+        }
+    };
+}
+
+// order matters: with `vip`, Books is `books-l2-tbt` and takes precedence over BooksL2Tbt
+impl_channel_event!(
+    channels: {
+        Instruments => Instruments,
+        MarkPrices => MarkPrices,
+        IndexTickers => IndexTickers,
+        Tickers => Tickers,
+        Trades => Trades,
+        TradesAll => TradesAll,
+        FundingRates => FundingRates,
+        OpenInterests => OpenInterests,
+        PriceLimits => PriceLimits,
+        LiquidationOrders => LiquidationOrders,
+        EstimatedPrices => EstimatedPrices,
+        OptSummaries => OptSummaries,
+        OptionTrades => OptionTrades,
+        Status => StatusChannel,
+        Candles => Candles,
+        MarkPriceCandles => MarkPriceCandles,
+        IndexCandles => IndexCandles,
+        Books => Books,
+        Books5 => Books5,
+        BboTbt => BboTbt,
+        BooksL2Tbt => BooksL2Tbt,
+        Positions => PositionsChannel,
+        Account => AccountChannel,
+        BalanceAndPosition => BalanceAndPositionChannel,
+        Orders => OrdersChannel,
+        AlgoOrders => AlgoOrdersChannel,
+        AlgoAdvance => AlgoAdvanceChannel,
+        LiquidationWarning => LiquidationWarningChannel,
+        AccountGreeks => AccountGreeksChannel,
+        DepositInfo => DepositInfoChannel,
+        WithdrawalInfo => WithdrawalInfoChannel,
+    }
+    ops: {
+        OrderOp => OrderOp,
+        BatchOrdersOp => BatchOrdersOp,
+        CancelOrderOp => CancelOrderOp,
+        BatchCancelOrdersOp => BatchCancelOrdersOp,
+        AmendOrderOp => AmendOrderOp,
+        BatchAmendOrdersOp => BatchAmendOrdersOp,
+        MassCancelOp => MassCancelOp,
+    }
+);
+
+impl<'a> ChannelEvent<'a> {
+    /// Parses the routing fields (`op`, `event`, `arg.channel`) of a message once and
+    /// deserializes it into the matching channel's response.
+    ///
+    /// Returns `Ok(None)` for unsupported channels and control events other than errors,
+    /// and `Err` for `event: error` frames. Use [`WsMessage::try_parse`] to receive
+    /// control events as well.
+    ///
+    /// ```
+    /// use okx_rs::api::v5::ws_convert::ChannelEvent;
+    ///
+    /// let msg = r#"{"arg":{"channel":"mark-price","instId":"BTC-USDT"},"data":[{"instType":"MARGIN","instId":"BTC-USDT","markPx":"42310.6","ts":"1630049139746"}]}"#;
+    /// match ChannelEvent::try_parse(msg).unwrap() {
+    ///     Some(ChannelEvent::MarkPrices(response)) => {
+    ///         assert_eq!(response.data.unwrap()[0].mark_price, Some(42310.6));
+    ///     }
+    ///     other => panic!("unexpected event: {:?}", other),
+    /// }
+    /// ```
+    pub fn try_parse(msg: &'a str) -> Result<Option<Self>, Error<()>> {
+        Self::from_message(WsMessage::try_parse(msg)?)
+    }
+
+    /// Same as [`ChannelEvent::try_parse`], deserializing with simd-json.
+    /// `msg` is modified in place and must not be reused.
+    #[cfg(feature = "simd")]
+    pub fn try_parse_simd(msg: &'a mut str) -> Result<Option<Self>, Error<()>> {
+        Self::from_message(WsMessage::try_parse_simd(msg)?)
+    }
+
+    fn from_message(message: Option<WsMessage<'a>>) -> Result<Option<Self>, Error<()>> {
+        match message {
+            Some(WsMessage::Channel(event)) => Ok(Some(event)),
+            Some(WsMessage::Control(ControlEvent::Error { code, msg, conn_id })) => {
+                Err(Error::Api(ApiError {
+                    code,
+                    msg,
+                    data: Some(()),
+                    conn_id,
+                }))
+            }
+            Some(WsMessage::Control(_)) | None => Ok(None),
+        }
+    }
+
+    fn parse<T: Deserialize<'a>>(msg: &'a str) -> Result<T, Error<()>> {
+        deser_from_str(msg).map_err(|err| {
+            log_parse_error(msg, &err);
+            err.into()
+        })
+    }
+
+    /// Latest exchange timestamp in milliseconds carried by the push data:
+    /// `ts` of market data, `uTime` of orders, positions and account updates.
+    /// `None` for channels without one and for op responses.
+    pub fn exchange_ts(&self) -> Option<u64> {
+        fn latest<T>(data: Option<&[T]>, ts: impl Fn(&T) -> Option<u64>) -> Option<u64> {
+            data?.iter().filter_map(ts).max()
+        }
+        match self {
+            Self::Books(response)
+            | Self::Books5(response)
+            | Self::BboTbt(response)
+            | Self::BooksL2Tbt(response) => {
+                latest(response.data.as_ref().map(|d| &d[..]), |d| d.ts)
+            }
+            Self::Orders(response) => latest(response.data.as_ref().map(|d| &d[..]), |d| d.u_time),
+            Self::AlgoOrders(response) => latest(response.data.as_deref(), |d| d.u_time),
+            Self::AlgoAdvance(response) => latest(response.data.as_deref(), |d| d.p_time),
+            Self::Positions(response) | Self::LiquidationWarning(response) => {
+                latest(response.data.as_deref(), |d| d.u_time)
+            }
+            Self::Account(response) => latest(response.data.as_deref(), |d| d.u_time),
+            Self::Tickers(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::Trades(response) | Self::TradesAll(response) => {
+                latest(response.data.as_deref(), |d| d.ts)
+            }
+            Self::OptionTrades(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::IndexTickers(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::FundingRates(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::OpenInterests(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::PriceLimits(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::EstimatedPrices(response) => latest(response.data.as_deref(), |d| d.ts),
+            Self::OptSummaries(response) => latest(response.data.as_deref(), |d| d.ts),
+            _ => None,
+        }
+    }
+}
+
+/// `event` frames sent by OKX outside of channel push data
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlEvent {
+    /// Subscription confirmed, `arg` echoes the subscribe request arg
+    SubscribeAck {
+        arg: serde_json::Value,
+        conn_id: Option<String>,
+    },
+    /// Unsubscription confirmed, `arg` echoes the unsubscribe request arg
+    UnsubscribeAck {
+        arg: serde_json::Value,
+        conn_id: Option<String>,
+    },
+    /// Login succeeded. Failed logins are reported as [`ControlEvent::Error`]
+    LoginOk { conn_id: Option<String> },
+    /// Rejected request, e.g. invalid args or failed login
+    Error {
+        code: Option<u64>,
+        msg: Option<String>,
+        conn_id: Option<String>,
+    },
+    /// Service notice, e.g. code `64008` warns the connection will soon be closed for a service upgrade
+    Notice {
+        code: Option<u64>,
+        msg: Option<String>,
+        conn_id: Option<String>,
+    },
+    /// Number of connections subscribed to a private channel
+    ChannelConnCount {
+        channel: String,
+        conn_count: Option<u64>,
+        conn_id: Option<String>,
+    },
+    /// Subscription rejected because the channel reached its connection limit
+    ChannelConnCountError {
+        channel: String,
+        conn_count: Option<u64>,
+        conn_id: Option<String>,
+    },
+    /// Event not known to this crate
+    Other {
+        event: String,
+        code: Option<u64>,
+        msg: Option<String>,
+        conn_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ControlFrame<'a> {
+    #[serde(borrow)]
+    event: &'a str,
+    #[serde(default, with = "str_opt")]
+    code: Option<u64>,
+    #[serde(default)]
+    msg: Option<String>,
+    #[serde(default)]
+    conn_id: Option<String>,
+    #[serde(default)]
+    arg: Option<serde_json::Value>,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default, with = "str_opt")]
+    conn_count: Option<u64>,
+}
+
+impl<'a> From<ControlFrame<'a>> for ControlEvent {
+    fn from(frame: ControlFrame<'a>) -> Self {
+        let ControlFrame {
+            event,
+            code,
+            msg,
+            conn_id,
+            arg,
+            channel,
+            conn_count,
+        } = frame;
+        let msg = msg.filter(|msg| !msg.is_empty());
+        match event {
+            "subscribe" => ControlEvent::SubscribeAck {
+                arg: arg.unwrap_or_default(),
+                conn_id,
+            },
+            "unsubscribe" => ControlEvent::UnsubscribeAck {
+                arg: arg.unwrap_or_default(),
+                conn_id,
+            },
+            "login" => ControlEvent::LoginOk { conn_id },
+            "error" => ControlEvent::Error { code, msg, conn_id },
+            "notice" => ControlEvent::Notice { code, msg, conn_id },
+            "channel-conn-count" => ControlEvent::ChannelConnCount {
+                channel: channel.unwrap_or_default(),
+                conn_count,
+                conn_id,
+            },
+            "channel-conn-count-error" => ControlEvent::ChannelConnCountError {
+                channel: channel.unwrap_or_default(),
+                conn_count,
+                conn_id,
+            },
+            other => ControlEvent::Other {
+                event: other.to_string(),
+                code,
+                msg,
+                conn_id,
+            },
+        }
+    }
+}
+
+/// Any message received on a websocket connection
+// channel events are the hot path and are kept inline instead of boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum WsMessage<'a> {
+    Channel(ChannelEvent<'a>),
+    Control(ControlEvent),
+}
+
+impl<'a> WsMessage<'a> {
+    /// Parses the routing fields (`op`, `event`, `arg.channel`) of a message once and
+    /// dispatches it to a [`ControlEvent`] or a typed [`ChannelEvent`].
+    ///
+    /// Returns `Ok(None)` for channels and ops not supported by this crate.
+    ///
+    /// ```
+    /// use okx_rs::api::v5::ws_convert::{ControlEvent, WsMessage};
+    ///
+    /// let msg = r#"{"event":"subscribe","arg":{"channel":"books5","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#;
+    /// match WsMessage::try_parse(msg).unwrap() {
+    ///     Some(WsMessage::Control(ControlEvent::SubscribeAck { arg, .. })) => {
+    ///         assert_eq!(arg["instId"], "BTC-USDT");
+    ///     }
+    ///     other => panic!("unexpected message: {:?}", other),
+    /// }
+    /// ```
+    pub fn try_parse(msg: &'a str) -> Result<Option<Self>, Error<()>> {
+        let route: Route = ChannelEvent::parse(msg)?;
+        Self::dispatch(route, msg)
+    }
+
+    /// Same as [`WsMessage::try_parse`], deserializing with simd-json.
+    /// The message is parsed once and routed on the parsed value.
+    /// `msg` is modified in place and must not be reused.
+    #[cfg(feature = "simd")]
+    pub fn try_parse_simd(msg: &'a mut str) -> Result<Option<Self>, Error<()>> {
+        let value = simd_json::to_borrowed_value(unsafe { msg.as_bytes_mut() })
+            .inspect_err(log_simd_parse_error)?;
+        let route = Route::from_value(&value);
+        Self::dispatch(route, value)
+    }
+
+    fn dispatch<S: MessageSource<'a>>(
+        route: Route<'_>,
+        source: S,
+    ) -> Result<Option<Self>, Error<()>> {
+        match route {
+            Route { event: Some(_), .. } => {
+                let frame: ControlFrame = source.deserialize()?;
+                let event = ControlEvent::from(frame);
+                match &event {
+                    ControlEvent::Error { .. } | ControlEvent::ChannelConnCountError { .. } => {
+                        log::error!("{:?}", event)
+                    }
+                    ControlEvent::Notice { .. } => log::warn!("{:?}", event),
+                    _ => log::info!("{:?}", event),
+                }
+                Ok(Some(WsMessage::Control(event)))
+            }
+            Route { op: Some(op), .. } => {
+                Ok(ChannelEvent::from_op(&op, source)?.map(WsMessage::Channel))
+            }
+            Route {
+                arg: Some(RouteArg {
+                    channel: Some(channel),
+                }),
+                ..
+            } => Ok(ChannelEvent::from_channel(&channel, source)?.map(WsMessage::Channel)),
+            _ => Ok(None),
+        }
+    }
 }
 
 impl Books {
+    /// Parses `books` (`books-l2-tbt` with `vip`) and `bbo-tbt` push data.
+    ///
+    /// Returns `Err` for `event: error` frames and malformed messages, like [`TryParseEvent::try_parse`].
     #[cfg(not(feature = "simd"))]
-    pub fn try_parse_books(
-        msg: &str,
-    ) -> Option<
-        WsResponse<
-            '_,
-            <Self as WebsocketChannel>::ArgType<'_>,
-            <Self as WebsocketChannel>::Response<'_>,
-        >,
-    > {
+    pub fn try_parse_books(msg: &str) -> Result<Option<ChannelResponse<'_, Self>>, Error<()>> {
         if msg.contains(Books::CHANNEL_PATTERN) || msg.contains(BboTbt::CHANNEL_PATTERN) {
-            let response: WsResponse<
-                <Self as WebsocketChannel>::ArgType<'_>,
-                <Self as WebsocketChannel>::Response<'_>,
-            > = deser_from_str(msg).unwrap();
-            if response.event == Some("error") {
-                log::error!("{:?}", response);
-                return None;
-            } else if response.event == Some("subscribe") || response.event == Some("unsubscribe") {
-                log::info!("{:?}", response);
-                return None;
-            }
-            Some(response)
+            match deser_from_str(msg) {
+                Ok(response) => filter_response(response),
+                Err(err) => {
+                    log_parse_error(msg, &err);
+                    Err(err.into())
+                }
+            }
         } else {
-            None
+            Ok(None)
         }
     }
 
+    /// Parses `books` (`books-l2-tbt` with `vip`) and `bbo-tbt` push data with simd-json.
+    /// `msg` is modified in place and must not be reused.
+    ///
+    /// Returns `Err` for `event: error` frames and malformed messages, like [`TryParseEvent::try_parse`].
     #[cfg(feature = "simd")]
-    pub fn try_parse_books<'a>(
-        msg: &'a mut str,
-    ) -> Option<
-        WsResponse<
-            'a,
-            <Self as WebsocketChannel>::ArgType<'a>,
-            <Self as WebsocketChannel>::Response<'a>,
-        >,
-    > {
+    pub fn try_parse_books(msg: &mut str) -> Result<Option<ChannelResponse<'_, Self>>, Error<()>> {
         if msg.contains(Books::CHANNEL_PATTERN) || msg.contains(BboTbt::CHANNEL_PATTERN) {
-            let response: WsResponse<
-                <Self as WebsocketChannel>::ArgType<'_>,
-                <Self as WebsocketChannel>::Response<'_>,
-            > = deser_from_str_simd(msg).unwrap();
-            if response.event == Some("error") {
-                log::error!("{:?}", response);
-                return None;
-            } else if response.event == Some("subscribe") || response.event == Some("unsubscribe") {
-                log::info!("{:?}", response);
-                return None;
-            }
-            Some(response)
+            match deser_from_str_simd(msg) {
+                Ok(response) => filter_response(response),
+                Err(err) => {
+                    log_simd_parse_error(&err);
+                    Err(err.into())
+                }
+            }
         } else {
-            None
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_channel_event {
+    use crate::api::error::Error;
+    use crate::api::v5::ws_convert::{ChannelEvent, TryParseEvent};
+    use crate::api::v5::{
+        AlgoOrderState, AlgoOrderType, Candles, DepositStatus, MarkPriceCandles, OptionType,
+        PositionSide, Side, WithdrawalStatus,
+    };
+
+    #[test]
+    fn test_route_by_channel() {
+        let msg = r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[["8476.98","415","0","13"],["8477","7","0","2"],["8477.34","85","0","1"],["8477.56","1","0","1"],["8505.84","8","0","1"]],"bids":[["8476.97","256","0","12"],["8475.55","101","0","1"],["8475.54","100","0","1"],["8475.3","1","0","1"],["8475.2","1","0","1"]],"instId":"BTC-USDT","ts":"1597026383085","seqId":123456}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(event @ ChannelEvent::Books5(_)) => assert_eq!(event.channel(), "books5"),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_route_candles() {
+        let msg = r#"{"arg":{"channel":"candle1D","instId":"BTC-USDT"},"data":[["1597026383085","8533.02","8553.74","8527.17","8548.26","45247","529.5858061","5063","0"]]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::Candles(response)) => {
+                assert_eq!(response.data.unwrap()[0].vol, Some(45247.0));
+            }
+            other => panic!("unexpected event: {:?}", other),
         }
+
+        let msg = r#"{"arg":{"channel":"mark-price-candle1m","instId":"BTC-USD-SWAP"},"data":[["1597026383085","3.721","3.743","3.677","3.708","0"]]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::MarkPriceCandles(_)))
+        ));
+        assert!(matches!(MarkPriceCandles::try_parse(msg), Ok(Some(_))));
+        assert!(matches!(Candles::try_parse(msg), Ok(None)));
+
+        let msg = r#"{"arg":{"channel":"index-candle30m","instId":"BTC-USD"},"data":[["1597026383085","3811.31","3811.31","3811.31","3811.31","1"]]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::IndexCandles(_)))
+        ));
+    }
+
+    #[test]
+    fn test_route_market_data() {
+        let msg = r#"{"arg":{"channel":"tickers","instId":"BTC-USDT"},"data":[{"instType":"SPOT","instId":"BTC-USDT","last":"9999.99","lastSz":"0.1","askPx":"9999.99","askSz":"11","bidPx":"8888.88","bidSz":"5","open24h":"9000","high24h":"10000","low24h":"8888.88","volCcy24h":"2222","vol24h":"2222","sodUtc0":"2222","sodUtc8":"2222","ts":"1597026383085"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::Tickers(response)) => {
+                let ticker = &response.data.unwrap()[0];
+                assert_eq!(ticker.inst_id, "BTC-USDT");
+                assert_eq!(ticker.open_24h, Some(9000.0));
+                assert_eq!(ticker.ts, Some(1597026383085));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"130639474","px":"42219.9","sz":"0.12060306","side":"buy","ts":"1630048897897","count":"3"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::Trades(response)) => {
+                let trade = &response.data.unwrap()[0];
+                assert_eq!(trade.trade_id, "130639474");
+                assert_eq!(trade.side, Some(Side::Buy));
+                assert_eq!(trade.count, Some(3));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"trades-all","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"130639474","px":"42219.9","sz":"0.12060306","side":"sell","ts":"1630048897897"}]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::TradesAll(_)))
+        ));
+
+        let msg = r#"{"arg":{"channel":"funding-rate","instId":"BTC-USD-SWAP"},"data":[{"fundingRate":"0.0001875391284828","fundingTime":"1700726400000","instId":"BTC-USD-SWAP","instType":"SWAP","method":"next_period","maxFundingRate":"0.00375","minFundingRate":"-0.00375","nextFundingRate":"0.0002608059239328","nextFundingTime":"1700755200000","premium":"0.0001233824646391","settFundingRate":"0.0001699799259033","settState":"settled","ts":"1700724675402"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::FundingRates(response)) => {
+                let rate = &response.data.unwrap()[0];
+                assert_eq!(rate.min_funding_rate, Some(-0.00375));
+                assert_eq!(rate.sett_state, Some("settled"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"open-interest","instId":"LTC-USD-SWAP"},"data":[{"instType":"SWAP","instId":"LTC-USD-SWAP","oi":"5000","oiCcy":"555.55","oiUsd":"50000","ts":"1597026383085"}]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::OpenInterests(_)))
+        ));
+
+        let msg = r#"{"arg":{"channel":"price-limit","instId":"LTC-USD-190628"},"data":[{"instId":"LTC-USD-190628","buyLmt":"200","sellLmt":"300","ts":"1597026383085","enabled":true}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::PriceLimits(response)) => {
+                let limit = &response.data.unwrap()[0];
+                assert_eq!(limit.buy_lmt, Some(200.0));
+                assert_eq!(limit.enabled, Some(true));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"liquidation-orders","instType":"SWAP"},"data":[{"details":[{"bkLoss":"0","bkPx":"0.007831","ccy":"","posSide":"short","side":"buy","sz":"13","ts":"1692266434010"}],"instFamily":"IOST-USDT","instId":"IOST-USDT-SWAP","instType":"SWAP","uly":"IOST-USDT"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::LiquidationOrders(response)) => {
+                let order = &response.data.unwrap()[0];
+                assert_eq!(order.inst_id, "IOST-USDT-SWAP");
+                assert_eq!(order.details[0].pos_side, Some(PositionSide::Short));
+                assert_eq!(order.details[0].sz, Some(13.0));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"status"},"data":[{"begin":"1672823400000","end":"1672825980000","href":"","preOpenBegin":"","scheDesc":"","serviceType":"0","state":"scheduled","maintType":"1","env":"1","system":"unified","title":"Trading account WebSocket system upgrade","ts":"1672826038470"}]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::Status(_)))
+        ));
+
+        let msg = r#"{"arg":{"channel":"estimated-price","instType":"FUTURES","instFamily":"BTC-USD"},"data":[{"instId":"BTC-USD-170310","instType":"FUTURES","settleType":"settlement","settlePx":"200","ts":"1597026383085"}]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::EstimatedPrices(_)))
+        ));
+    }
+
+    #[test]
+    fn test_route_options() {
+        let msg = r#"{"arg":{"channel":"opt-summary","instFamily":"BTC-USD"},"data":[{"instType":"OPTION","instId":"BTC-USD-241013-70000-P","uly":"BTC-USD","delta":"-1.1180902625","gamma":"2.2361957091","vega":"0.0000000001","theta":"0.0000032334","lever":"8.465747567","markVol":"0.3675503331","bidVol":"0","askVol":"1.1669998535","realVol":"","deltaBS":"-0.9999672034","gammaBS":"0.0000000002","thetaBS":"28.2649858387","vegaBS":"0.0000114332","ts":"1728703155650","fwdPx":"62604.6993093463","volLv":"0.2044711229"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::OptSummaries(response)) => {
+                assert_eq!(response.arg.unwrap().inst_family, Some("BTC-USD"));
+                let summary = &response.data.unwrap()[0];
+                assert_eq!(summary.delta, Some(-1.1180902625));
+                assert_eq!(summary.delta_bs, Some(-0.9999672034));
+                assert_eq!(summary.real_vol, None);
+                assert_eq!(summary.vol_lv, Some(0.2044711229));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"option-trades","instType":"OPTION","instFamily":"BTC-USD"},"data":[{"fillVol":"0.5066007836914062","fwdPx":"16469.69928595038","idxPx":"16537.2","instFamily":"BTC-USD","instId":"BTC-USD-221230-4000-C","markPx":"0.09003405014712008","optType":"C","px":"0.0885","side":"sell","sz":"2","tradeId":"1","ts":"1672286551080"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::OptionTrades(response)) => {
+                let trade = &response.data.unwrap()[0];
+                assert_eq!(trade.option_type, Some(OptionType::Call));
+                assert_eq!(trade.fill_vol, Some(0.5066007836914062));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_no_false_positive_on_payload() {
+        // instrument payload mentioning another channel's pattern must not be routed there
+        let msg = r#"{"arg":{"channel":"mark-price","instId":"BTC-USDT"},"data":[{"instType":"MARGIN","instId":"\"channel\":\"books\"","markPx":"42310.6","ts":"1630049139746"}]}"#;
+        assert!(matches!(
+            ChannelEvent::try_parse(msg),
+            Ok(Some(ChannelEvent::MarkPrices(_)))
+        ));
+    }
+
+    #[test]
+    fn test_route_private() {
+        let msg = r#"{"arg":{"channel":"orders-algo","uid":"77982378738415879","instType":"FUTURES","instId":"BTC-USD-200329"},"data":[{"actualPx":"0","actualSide":"","actualSz":"0","algoClOrdId":"","algoId":"581878926302093312","cTime":"1685002746818","ccy":"","clOrdId":"","failCode":"","instId":"BTC-USD-200329","instType":"FUTURES","lever":"20","notionalUsd":"","ordId":"","ordPx":"","ordType":"trigger","posSide":"net","reduceOnly":"false","side":"buy","slOrdPx":"","slTriggerPx":"","slTriggerPxType":"","state":"live","sz":"2","tag":"","tdMode":"cross","tgtCcy":"","tpOrdPx":"","tpTriggerPx":"","tpTriggerPxType":"","triggerPx":"1000","triggerTime":"","uTime":"1685002746818"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::AlgoOrders(response)) => {
+                let order = &response.data.unwrap()[0];
+                assert_eq!(order.algo_id, "581878926302093312");
+                assert!(matches!(order.ord_type, Some(AlgoOrderType::Trigger)));
+                assert!(matches!(order.state, Some(AlgoOrderState::Live)));
+                assert_eq!(order.reduce_only, Some(false));
+                assert_eq!(order.trigger_px, Some(1000.0));
+                assert_eq!(order.ord_px, None);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"algo-advance","uid":"77982378738415879","instType":"SPOT","instId":"BTC-USDT"},"data":[{"actualPx":"","actualSide":"","actualSz":"0","algoId":"355056228680335360","cTime":"1630924001545","ccy":"","instId":"BTC-USDT","instType":"SPOT","lever":"0","ordType":"iceberg","pTime":"1630924295204","posSide":"net","pxLimit":"10","pxSpread":"1","pxVar":"","side":"buy","state":"pause","sz":"0.1","szLimit":"0.1","tdMode":"cash","timeInterval":"","triggerTime":""}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::AlgoAdvance(response)) => {
+                let order = &response.data.unwrap()[0];
+                assert!(matches!(order.ord_type, Some(AlgoOrderType::Iceberg)));
+                assert!(matches!(order.state, Some(AlgoOrderState::Pause)));
+                assert_eq!(order.px_limit, Some(10.0));
+                assert_eq!(order.time_interval, None);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"liquidation-warning","uid":"77982378738415879","instType":"ANY"},"data":[{"adl":"1","cTime":"1619507758793","ccy":"ETH","instId":"ETH-USD-210430","instType":"FUTURES","lever":"10","liqPx":"0.01","markPx":"2353.849","mgnMode":"isolated","mgnRatio":"11.731726509588816","pTime":"1619507761462","pos":"1","posCcy":"","posId":"307173036051017730","posSide":"long","uTime":"1619507761462"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::LiquidationWarning(response)) => {
+                let position = &response.data.unwrap()[0];
+                assert_eq!(position.pos_id, "307173036051017730");
+                assert_eq!(position.liq_px, Some(0.01));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"account-greeks","uid":"77982378738415879"},"data":[{"thetaBS":"","thetaPA":"","deltaBS":"","deltaPA":"","gammaBS":"","gammaPA":"","vegaBS":"","vegaPA":"","ccy":"BTC","ts":"1620282889345"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::AccountGreeks(response)) => {
+                let greeks = &response.data.unwrap()[0];
+                assert_eq!(greeks.ccy, "BTC");
+                assert_eq!(greeks.delta_bs, None);
+                assert_eq!(greeks.ts, Some(1620282889345));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"deposit-info","uid":"289320****60975104"},"data":[{"actualDepBlkConfirm":"0","amt":"1","areaCodeFrom":"","ccy":"USDT","chain":"USDT-TRC20","depId":"88165462","from":"","fromWdId":"","pTime":"1674103661147","state":"0","subAcct":"test","to":"TEhFAqpuHa3LY*****8ByNoGnrmexeGMw","ts":"1674103661123","txId":"bc5376817*****************dbb0d729f6b","uid":"289320****60975104"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::DepositInfo(response)) => {
+                let deposit = &response.data.unwrap()[0];
+                assert_eq!(deposit.dep_id.as_deref(), Some("88165462"));
+                assert!(matches!(
+                    deposit.state,
+                    Some(DepositStatus::WaitingForConfirmation)
+                ));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"withdrawal-info","uid":"289320*****0975104"},"data":[{"addrEx":null,"amt":"2","areaCodeFrom":"","areaCodeTo":"","ccy":"USDT","chain":"USDT-TRC20","clientId":"","fee":"0.8","feeCcy":"USDT","from":"","memo":"","nonTradableAsset":false,"pTime":"1674103268578","pmtId":"","state":"0","subAcct":"test","tag":"","to":"TN8CKTQMnpWfT******8KipbJ24ErguhF","ts":"1674103268472","txId":"","uid":"289333*****1101696","wdId":"63754560"}]}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::WithdrawalInfo(response)) => {
+                let withdrawal = &response.data.unwrap()[0];
+                assert_eq!(withdrawal.wd_id.as_deref(), Some("63754560"));
+                assert!(matches!(withdrawal.state, WithdrawalStatus::Pending));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_route_by_op() {
+        let msg = r#"{"id":"1512","op":"order","data":[{"clOrdId":"","ordId":"12345689","tag":"","sCode":"0","sMsg":""}],"code":"0","msg":""}"#;
+        match ChannelEvent::try_parse(msg).unwrap() {
+            Some(ChannelEvent::OrderOp(response)) => {
+                assert_eq!(response.id, Some("1512"));
+                assert_eq!(response.data.unwrap()[0].s_code, Some(0));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        let msg = r#"{"id":"1513","op":"unknown-op","data":[],"code":"0","msg":""}"#;
+        assert!(matches!(ChannelEvent::try_parse(msg), Ok(None)));
+    }
+
+    #[test]
+    fn test_events() {
+        let msg = r#"{"event":"subscribe","arg":{"channel":"books5","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#;
+        assert!(matches!(ChannelEvent::try_parse(msg), Ok(None)));
+
+        let msg = r#"{"event":"error","code":"60012","msg":"Invalid request","connId":"a4d3ae55"}"#;
+        match ChannelEvent::try_parse(msg) {
+            Err(Error::Api(err)) => {
+                assert_eq!(err.code, Some(60012));
+                assert_eq!(err.conn_id.as_deref(), Some("a4d3ae55"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let msg = r#"{"arg":{"channel":"unsupported"},"data":[]}"#;
+        assert!(matches!(ChannelEvent::try_parse(msg), Ok(None)));
+    }
+}
+
+#[cfg(test)]
+mod test_control_event {
+    use crate::api::v5::ws_convert::{ChannelEvent, ControlEvent, WsMessage};
+
+    fn control(msg: &str) -> ControlEvent {
+        match WsMessage::try_parse(msg) {
+            Ok(Some(WsMessage::Control(event))) => event,
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_acks() {
+        let event = control(
+            r#"{"event":"unsubscribe","arg":{"channel":"orders","instType":"ANY"},"connId":"a4d3ae55"}"#,
+        );
+        assert_eq!(
+            event,
+            ControlEvent::UnsubscribeAck {
+                arg: serde_json::json!({"channel": "orders", "instType": "ANY"}),
+                conn_id: Some("a4d3ae55".into()),
+            }
+        );
+
+        let event = control(r#"{"event":"login","code":"0","msg":"","connId":"a4d3ae55"}"#);
+        assert_eq!(
+            event,
+            ControlEvent::LoginOk {
+                conn_id: Some("a4d3ae55".into())
+            }
+        );
+    }
+
+    #[test]
+    fn test_error_with_escaped_msg() {
+        let event = control(
+            r#"{"event":"error","code":"60012","msg":"Invalid request: {\"op\": \"subscribe\", \"argss\":[{ \"channel\" : \"books\"}]}","connId":"a4d3ae55"}"#,
+        );
+        match event {
+            ControlEvent::Error { code, msg, .. } => {
+                assert_eq!(code, Some(60012));
+                assert!(msg.unwrap().contains(r#""argss""#));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_notice_and_conn_count() {
+        let msg = r#"{"event":"notice","code":"64008","msg":"The connection will soon be closed for a service upgrade. Please reconnect.","connId":"a4d3ae55"}"#;
+        assert!(matches!(
+            control(msg),
+            ControlEvent::Notice {
+                code: Some(64008),
+                ..
+            }
+        ));
+        // notices are not errors for channel consumers
+        assert!(matches!(ChannelEvent::try_parse(msg), Ok(None)));
+
+        let event = control(
+            r#"{"event":"channel-conn-count","channel":"orders","connCount":"2","connId":"abcd1234"}"#,
+        );
+        assert_eq!(
+            event,
+            ControlEvent::ChannelConnCount {
+                channel: "orders".into(),
+                conn_count: Some(2),
+                conn_id: Some("abcd1234".into()),
+            }
+        );
+    }
+}
+
+#[cfg(all(test, feature = "simd"))]
+mod test_simd {
+    use crate::api::error::Error;
+    use crate::api::v5::ws_convert::{ChannelEvent, ControlEvent, TryParseEvent, WsMessage};
+    use crate::api::v5::MarkPrices;
+    use crate::websocket::conn::Books;
+    use crate::websocket::WebsocketChannel;
+
+    const MESSAGES: [&str; 5] = [
+        r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[["8476.98","415","0","13"]],"bids":[["8476.97","256","0","12"]],"instId":"BTC-USDT","ts":"1597026383085","seqId":123456}]}"#,
+        r#"{"arg":{"channel":"mark-price","instId":"BTC-USDT"},"data":[{"instType":"MARGIN","instId":"BTC-USDT","markPx":"42310.6","ts":"1630049139746"}]}"#,
+        r#"{"arg":{"channel":"orders","instType":"ANY","uid":"77982378738415879"},"data":[{"instType":"SPOT","instId":"BTC-USDT","ordId":"312269865356374016","clOrdId":"b1","px":"40000","sz":"0.001","ordType":"limit","side":"buy","state":"live","cTime":"1597026383085","uTime":"1597026383085"}]}"#,
+        r#"{"id":"1512","op":"order","data":[{"clOrdId":"","ordId":"12345689","tag":"","sCode":"0","sMsg":""}],"code":"0","msg":""}"#,
+        r#"{"arg":{"channel":"unsupported"},"data":[]}"#,
+    ];
+
+    #[test]
+    fn test_same_as_serde_json() {
+        for msg in MESSAGES {
+            let expected = format!("{:?}", WsMessage::try_parse(msg).unwrap());
+            let mut buf = msg.to_string();
+            let parsed = format!("{:?}", WsMessage::try_parse_simd(&mut buf).unwrap());
+            assert_eq!(parsed, expected);
+        }
+    }
+
+    #[test]
+    fn test_control_with_escaped_msg() {
+        let mut msg = r#"{"event":"error","code":"60012","msg":"Invalid request: {\"op\": \"subscribe\"}","connId":"a4d3ae55"}"#.to_string();
+        match ChannelEvent::try_parse_simd(&mut msg) {
+            Err(Error::Api(err)) => {
+                assert_eq!(err.code, Some(60012));
+                assert_eq!(
+                    err.msg.as_deref(),
+                    Some(r#"Invalid request: {"op": "subscribe"}"#)
+                );
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let mut msg = r#"{"event":"subscribe","arg":{"channel":"books5","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#.to_string();
+        assert!(matches!(
+            WsMessage::try_parse_simd(&mut msg),
+            Ok(Some(WsMessage::Control(ControlEvent::SubscribeAck { .. })))
+        ));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut msg =
+            r#"{"arg":{"channel":"mark-price","instId":"BTC-USDT"},"data":[{"markPx":}]}"#
+                .to_string();
+        assert!(matches!(
+            WsMessage::try_parse_simd(&mut msg.clone()),
+            Err(Error::SimdJson(_))
+        ));
+        assert!(matches!(
+            MarkPrices::try_parse_simd(&mut msg),
+            Err(Error::SimdJson(_))
+        ));
+
+        let mut msg = format!(
+            r#"{{"arg":{{"channel":"{}","instId":"BTC-USDT"}},"action":"snapshot","data":[{{"asks":[["1"]]}}]}}"#,
+            Books::CHANNEL
+        );
+        assert!(Books::try_parse_books(&mut msg).is_err());
     }
 }
@@ -0,0 +1,132 @@
+//! Views of an [`OrderBook`] grouped into coarser price buckets, e.g. 10 × `tickSz`.
+//!
+//! Bids are rounded down and asks up to the bucket, so that a bucket never shows a better
+//! price than the levels it holds and the two sides never overlap. Buckets are computed
+//! while iterating the book, nothing is copied.
+
+use std::iter::{FusedIterator, Peekable};
+
+use rust_decimal::Decimal;
+
+use crate::api::v5::Side;
+use crate::book::{DepthSnapshot, Fixed, OrderBook};
+
+/// `(price, size)` buckets of one side from the best price, see [`OrderBook::aggregated_bids`]
+#[derive(Debug, Clone)]
+pub struct Aggregated<I: Iterator<Item = (Fixed, Fixed)>> {
+    levels: Peekable<I>,
+    side: Side,
+    increment: Fixed,
+}
+
+impl<I: Iterator<Item = (Fixed, Fixed)>> Aggregated<I> {
+    /// Groups `levels` of `side`, given from the best price.
+    /// Empty if `increment` is not positive.
+    pub fn new(levels: I, side: Side, increment: Fixed) -> Self {
+        Self {
+            levels: levels.peekable(),
+            side,
+            increment,
+        }
+    }
+
+    /// Price of the bucket holding `price`
+    fn bucket(&self, price: Fixed) -> Fixed {
+        let steps = price / self.increment;
+        let steps = match self.side {
+            Side::Buy => steps.floor(),
+            Side::Sell => steps.ceil(),
+        };
+        steps * self.increment
+    }
+}
+
+impl<I: Iterator<Item = (Fixed, Fixed)>> Iterator for Aggregated<I> {
+    type Item = (Fixed, Fixed);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.increment <= Decimal::ZERO {
+            return None;
+        }
+        let (price, mut size) = self.levels.next()?;
+        let bucket = self.bucket(price);
+        while let Some((price, level_sz)) = self.levels.peek().copied() {
+            if self.bucket(price) != bucket {
+                break;
+            }
+            size += level_sz;
+            self.levels.next();
+        }
+        Some((bucket, size))
+    }
+}
+
+impl<I: Iterator<Item = (Fixed, Fixed)>> FusedIterator for Aggregated<I> {}
+
+impl OrderBook {
+    /// Bids grouped into buckets of `increment`, rounded down
+    pub fn aggregated_bids(
+        &self,
+        increment: Fixed,
+    ) -> Aggregated<impl Iterator<Item = (Fixed, Fixed)> + '_> {
+        Aggregated::new(self.bids(), Side::Buy, increment)
+    }
+
+    /// Asks grouped into buckets of `increment`, rounded up
+    pub fn aggregated_asks(
+        &self,
+        increment: Fixed,
+    ) -> Aggregated<impl Iterator<Item = (Fixed, Fixed)> + '_> {
+        Aggregated::new(self.asks(), Side::Sell, increment)
+    }
+
+    /// First `n` buckets of `increment` of each side
+    pub fn aggregated_top(&self, increment: Fixed, n: usize) -> DepthSnapshot {
+        DepthSnapshot {
+            bids: self.aggregated_bids(increment).take(n).collect(),
+            asks: self.aggregated_asks(increment).take(n).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_aggregate {
+    use rust_decimal::Decimal;
+
+    use crate::book::OrderBook;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn levels<'a>(levels: &'a [(&str, &str)]) -> impl Iterator<Item = (Decimal, Decimal)> + 'a {
+        levels.iter().map(|(price, size)| (dec(price), dec(size)))
+    }
+
+    #[test]
+    fn test_aggregated() {
+        let book = OrderBook::from_levels(
+            levels(&[("100.4", "1"), ("100.1", "2"), ("100", "3"), ("99.9", "4")]),
+            levels(&[("100.5", "1"), ("100.9", "2"), ("101", "3"), ("101.1", "4")]),
+        );
+        assert_eq!(
+            book.aggregated_bids(dec("1")).collect::<Vec<_>>(),
+            [(dec("100"), dec("6")), (dec("99"), dec("4"))]
+        );
+        assert_eq!(
+            book.aggregated_asks(dec("1")).collect::<Vec<_>>(),
+            [(dec("101"), dec("6")), (dec("102"), dec("4"))]
+        );
+        // the tick size groups nothing
+        assert_eq!(
+            book.aggregated_bids(dec("0.1")).count(),
+            book.bids().count()
+        );
+
+        let top = book.aggregated_top(dec("0.5"), 2);
+        assert_eq!(top.bids, [(dec("100"), dec("6")), (dec("99.5"), dec("4"))]);
+        assert_eq!(top.asks, [(dec("100.5"), dec("1")), (dec("101"), dec("5"))]);
+
+        assert_eq!(book.aggregated_asks(Decimal::ZERO).next(), None);
+    }
+}
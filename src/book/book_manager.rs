@@ -1,5 +1,13 @@
-use crate::api::v5::{BookUpdate, Levels, Side};
-use crate::book::OrderBook;
+use std::cmp::Ordering;
+use std::sync::mpsc;
+
+use thiserror::Error;
+
+use crate::api::v5::{BookUpdate, Level, Side};
+use crate::book::events::{Bbo, BboSubscribers, BookChanges};
+use crate::book::snapshot::BookSnapshot;
+use crate::book::{Fixed, OrderBook};
+use crate::websocket::WebsocketChannel;
 
 type Seq = i64;
 
@@ -8,6 +16,42 @@ pub struct BookManager {
     book: OrderBook,
     pub last_seq: Option<Seq>,
     pub last_exch_ts: Option<u64>,
+    /// Set when an applied update left the book with a different checksum than the exchange's.
+    /// Updates are dropped as [`BookUpdateOutcome::Stale`] until the book is resynced, see [`BookManager::resync`],
+    /// or a new snapshot is pushed, e.g. after resubscribing or reconnecting.
+    pub checksum_mismatch: Option<ChecksumMismatch>,
+    changes: BookChanges,
+    bbo_subscribers: BboSubscribers,
+    /// Levels of the update being applied, parsed before the book is touched
+    parsed: Vec<(Fixed, Fixed, u32, Side)>,
+}
+
+/// Checksum of the book after applying an update differs from the `checksum` sent with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("book checksum mismatch at seqId {seq_id:?}: expected {expected}, computed {computed}")]
+pub struct ChecksumMismatch {
+    pub seq_id: Option<Seq>,
+    pub expected: i32,
+    pub computed: i32,
+}
+
+/// Result of [`BookManager::handle_book_update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookUpdateOutcome {
+    /// Applied on top of the book
+    Applied,
+    /// Same `seqId` as the last update applied, dropped
+    Duplicate,
+    /// Older than the last update applied, or received before the first snapshot. Dropped
+    Stale,
+    /// `prevSeqId` does not match the last `seqId` applied, updates were missed.
+    /// Dropped, the book has to be resynced, see [`BookManager::resync`]
+    GapDetected { expected: Seq, prev_seq_id: Seq },
+    /// Applied after a sequence reset, either a new snapshot or an update with `seqId` below `prevSeqId`
+    Reset,
+    /// A level's price or size is not a decimal number. Dropped without applying any of its levels,
+    /// so that sequenced books report the next update as [`BookUpdateOutcome::GapDetected`]
+    InvalidLevel,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -18,93 +62,575 @@ pub enum BookUpdateType {
 }
 
 impl BookManager {
-    #[allow(clippy::all)]
-    pub fn handle_book_update(&mut self, update: BookUpdate, update_type: BookUpdateType) -> bool {
-        let seq_id = update.seq_id.unwrap();
-        if seq_id < update.prev_seq_id.expect("no prev seq") {
-            // sequence reset due to maintenance. just panics for now
-            // TODO: handle seq reset
-            todo!("unhandled seq reset");
+    #[inline]
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Changes of the last update handled, empty if it was dropped
+    #[inline]
+    pub fn changes(&self) -> &BookChanges {
+        &self.changes
+    }
+
+    /// Calls `callback` with the top of book after each update changing it
+    pub fn on_bbo(&mut self, callback: impl FnMut(&Bbo) + Send + 'static) {
+        self.bbo_subscribers.push(Box::new(callback));
+    }
+
+    /// Channel receiving the top of book after each update changing it
+    pub fn subscribe_bbo(&mut self) -> mpsc::Receiver<Bbo> {
+        self.bbo_subscribers.channel()
+    }
+
+    /// Whether the book is out of sync and has to be rebuilt from a new snapshot
+    #[inline]
+    pub fn needs_resync(&self) -> bool {
+        self.checksum_mismatch.is_some()
+    }
+
+    /// Clears the book, the next update accepted is a snapshot
+    pub fn reset(&mut self) {
+        self.book = OrderBook::default();
+        self.changes.clear();
+        self.last_seq = None;
+        self.last_exch_ts = None;
+        self.checksum_mismatch = None;
+    }
+
+    /// Levels of the book with the sequence of the last update applied
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            last_seq: self.last_seq,
+            last_exch_ts: self.last_exch_ts,
+            bids: self.book.bids_with_orders().collect(),
+            asks: self.book.asks_with_orders().collect(),
         }
-        let should_update = if let Some(last_seq) = self.last_seq {
-            if seq_id == last_seq {
-                // TODO: verify all updates matches current book
-                // TODO: verify exch timestamp
-                // TODO: verify checksum
-                false
-            } else if seq_id > last_seq {
-                // TODO: commit update
-                true
-            } else {
-                // drop
-                false
+    }
+
+    /// Replaces the book with `snapshot`, updates following its `last_seq` are applied on top of it.
+    /// Callbacks and channels subscribed to the top of book are kept.
+    pub fn restore(&mut self, snapshot: BookSnapshot) {
+        self.reset();
+        self.book = OrderBook::from_levels_with_orders(snapshot.bids, snapshot.asks);
+        self.last_seq = snapshot.last_seq;
+        self.last_exch_ts = snapshot.last_exch_ts;
+    }
+
+    /// Clears the book and returns the `unsubscribe` and `subscribe` messages of `channel`,
+    /// to be sent on its connection so that the exchange pushes a new snapshot
+    pub fn resync<C: WebsocketChannel>(&mut self, channel: &C) -> [String; 2] {
+        self.reset();
+        [channel.unsubscribe_message(), channel.subscribe_message()]
+    }
+
+    /// Applies `update` if it follows the last one applied.
+    /// When it carries a `checksum`, the book is verified against it after applying.
+    ///
+    /// Sequencing uses `seqId` / `prevSeqId` when present, e.g. `books`. Channels without
+    /// `prevSeqId`, e.g. `books5` and `bbo-tbt`, are only ordered by `seqId`, or by `ts` without it.
+    pub fn handle_book_update(
+        &mut self,
+        update: BookUpdate,
+        update_type: BookUpdateType,
+    ) -> Result<BookUpdateOutcome, ChecksumMismatch> {
+        self.changes.clear();
+        if self.checksum_mismatch.is_some() {
+            if update_type != BookUpdateType::Snapshot {
+                return Ok(BookUpdateOutcome::Stale);
             }
-        } else {
-            // first book update has to be snapshot
-            update_type == BookUpdateType::Snapshot
+            // a new snapshot rebuilds the book, as after `resync`
+            self.reset();
+        }
+        let outcome = self.sequence(&update, &update_type);
+        match outcome {
+            BookUpdateOutcome::Applied | BookUpdateOutcome::Reset => {}
+            _ => return Ok(outcome),
+        }
+        if !self.parse_levels(&update, &update_type) {
+            log::warn!(
+                "dropped book update with invalid level at seqId {:?}",
+                update.seq_id
+            );
+            return Ok(BookUpdateOutcome::InvalidLevel);
+        }
+        let (best_bid, best_ask) = (self.book.best_bid(), self.book.best_ask());
+        if update_type == BookUpdateType::Snapshot {
+            self.book = OrderBook::default();
+            self.changes.reset = true;
+        }
+
+        let BookUpdate {
+            seq_id,
+            ts,
+            checksum,
+            ..
+        } = update;
+        let events = &mut self.changes.levels;
+        // imply depth levels if bbo
+        let bbo = update_type == BookUpdateType::BBO;
+        for &(price, size, orders, side) in &self.parsed {
+            self.book
+                .apply_level(price, size, orders, side, bbo, Some(events));
+        }
+        self.changes.bid_changed = self.book.best_bid() != best_bid;
+        self.changes.ask_changed = self.book.best_ask() != best_ask;
+
+        if seq_id.is_some() {
+            self.last_seq = seq_id;
+        }
+        if ts.is_some() {
+            self.last_exch_ts = ts;
+        }
+        if let Some(expected) = checksum {
+            let computed = self.book.checksum();
+            if computed != expected as i32 {
+                let mismatch = ChecksumMismatch {
+                    seq_id,
+                    expected: expected as i32,
+                    computed,
+                };
+                self.checksum_mismatch = Some(mismatch);
+                return Err(mismatch);
+            }
+        }
+        // println!("{:?}", self.book);
+        debug_assert!(!self.book.crossed(), "crossed book");
+        if self.changes.bbo_changed() && !self.bbo_subscribers.is_empty() {
+            self.bbo_subscribers.notify(&Bbo {
+                bid: self.book.best_bid(),
+                ask: self.book.best_ask(),
+                seq_id: self.last_seq,
+                ts: self.last_exch_ts,
+            });
+        }
+        Ok(outcome)
+    }
+
+    /// Parses the levels of `update` into `self.parsed`, only the best level of each side for `bbo-tbt`.
+    /// Returns `false` if a level is not a decimal number
+    fn parse_levels(&mut self, update: &BookUpdate, update_type: &BookUpdateType) -> bool {
+        self.parsed.clear();
+        let depth = match update_type {
+            BookUpdateType::BBO => 1,
+            _ => usize::MAX,
         };
+        for (levels, side) in [(&update.bids, Side::Buy), (&update.asks, Side::Sell)] {
+            for level in levels.iter().take(depth) {
+                match parse_level(level) {
+                    Some((price, size, orders)) => self.parsed.push((price, size, orders, side)),
+                    None => return false,
+                }
+            }
+        }
+        true
+    }
 
-        if should_update {
-            let BookUpdate {
-                seq_id,
-                ts,
-                bids,
-                asks,
-                ..
-            } = update;
-            self.last_seq = *seq_id;
-            self.last_exch_ts = Some(ts.expect("no ts"));
-
-            // imply depth levels if bbo
-            if update_type == BookUpdateType::BBO {
-                match bids {
-                    Levels::Depth1(bids) => {
-                        let bid = bids[0];
-                        self.book.handle_level(
-                            bid.price.parse().unwrap(),
-                            bid.size.parse().unwrap(),
-                            Side::Buy,
-                            true,
-                        );
+    /// Where `update` stands relative to the last update applied, without applying it
+    fn sequence(&self, update: &BookUpdate, update_type: &BookUpdateType) -> BookUpdateOutcome {
+        let synced = self.last_seq.is_some() || self.last_exch_ts.is_some();
+        if *update_type == BookUpdateType::Snapshot {
+            return match (update.prev_seq_id, update.ts, self.last_exch_ts) {
+                _ if !synced => BookUpdateOutcome::Applied,
+                // every push of e.g. `books5` is a snapshot, only ordered by `ts`
+                (None, Some(ts), Some(last_ts)) if ts < last_ts => BookUpdateOutcome::Stale,
+                (None, _, _) => BookUpdateOutcome::Applied,
+                // a snapshot of a sequenced book replaces it, e.g. after resubscribing or a sequence reset
+                (Some(_), _, _) => BookUpdateOutcome::Reset,
+            };
+        }
+        if !synced && *update_type == BookUpdateType::Diff {
+            // first book update has to be snapshot
+            return BookUpdateOutcome::Stale;
+        }
+
+        match (update.seq_id, update.prev_seq_id, self.last_seq) {
+            (Some(seq_id), Some(prev_seq_id), Some(last_seq)) if prev_seq_id >= 0 => {
+                if seq_id == last_seq {
+                    // TODO: verify all updates matches current book
+                    BookUpdateOutcome::Duplicate
+                } else if prev_seq_id == last_seq {
+                    // `seqId` below `prevSeqId`: sequence reset due to maintenance,
+                    // the update still applies on top of the book and later ones follow `seqId`
+                    if seq_id < prev_seq_id {
+                        BookUpdateOutcome::Reset
+                    } else {
+                        BookUpdateOutcome::Applied
                     }
-                    _ => unreachable!("not an bbo"),
-                }
-                match asks {
-                    Levels::Depth1(asks) => {
-                        let ask = asks[0];
-                        self.book.handle_level(
-                            ask.price.parse().unwrap(),
-                            ask.size.parse().unwrap(),
-                            Side::Sell,
-                            true,
-                        );
+                } else if seq_id < last_seq && seq_id > prev_seq_id {
+                    BookUpdateOutcome::Stale
+                } else {
+                    BookUpdateOutcome::GapDetected {
+                        expected: last_seq,
+                        prev_seq_id,
                     }
-                    _ => unreachable!("not an bbo"),
-                }
-            } else {
-                for bid in bids.iter() {
-                    self.book.handle_level(
-                        bid.price.parse().unwrap(),
-                        bid.size.parse().unwrap(),
-                        Side::Buy,
-                        false,
-                    );
-                }
-                for ask in asks.iter() {
-                    self.book.handle_level(
-                        ask.price.parse().unwrap(),
-                        ask.size.parse().unwrap(),
-                        Side::Sell,
-                        false,
-                    );
                 }
             }
-
-            self.last_seq = *seq_id;
-            self.last_exch_ts = *ts;
-            // println!("{:?}", self.book);
-            debug_assert!(!self.book.crossed(), "crossed book");
+            (Some(seq_id), _, Some(last_seq)) => match seq_id.cmp(&last_seq) {
+                Ordering::Greater => BookUpdateOutcome::Applied,
+                Ordering::Equal => BookUpdateOutcome::Duplicate,
+                Ordering::Less => BookUpdateOutcome::Stale,
+            },
+            _ => match (update.ts, self.last_exch_ts) {
+                (Some(ts), Some(last_ts)) if ts < last_ts => BookUpdateOutcome::Stale,
+                _ => BookUpdateOutcome::Applied,
+            },
         }
-        should_update
+    }
+}
+
+/// Price, size and number of orders of a level, `None` if the price or size is not a decimal number.
+/// The number of orders is zero when missing.
+fn parse_level(level: &Level) -> Option<(Fixed, Fixed, u32)> {
+    Some((
+        level.price.parse().ok()?,
+        level.size.parse().ok()?,
+        level.orders.parse().unwrap_or(0),
+    ))
+}
+
+#[cfg(test)]
+mod tests_book_manager {
+    use super::{BookManager, BookUpdateOutcome, BookUpdateType};
+    use crate::api::v5::BookUpdate;
+    use crate::websocket::checksum::crc32;
+    use crate::websocket::conn::Books;
+
+    fn update(asks: &str, bids: &str, prev_seq_id: i64, seq_id: i64, checksum: &[u8]) -> String {
+        format!(
+            r#"{{"asks":{asks},"bids":{bids},"ts":"1","checksum":{},"prevSeqId":{prev_seq_id},"seqId":{seq_id}}}"#,
+            crc32(checksum) as i32
+        )
+    }
+
+    #[test]
+    fn test_checksum_and_resync() {
+        let mut manager = BookManager::default();
+        let snapshot = update(
+            r#"[["8476.98","415","0","13"],["8477","7","0","2"]]"#,
+            r#"[["8476.97","256","0","12"],["8475.55","101","0","1"]]"#,
+            -1,
+            10,
+            b"8476.97:256:8476.98:415:8475.55:101:8477:7",
+        );
+        let snapshot = serde_json::from_str::<BookUpdate>(&snapshot).unwrap();
+        assert_eq!(
+            manager.handle_book_update(snapshot.clone(), BookUpdateType::Snapshot),
+            Ok(BookUpdateOutcome::Applied)
+        );
+
+        let valid = update(
+            r#"[["8477","0","0","0"]]"#,
+            "[]",
+            10,
+            11,
+            b"8476.97:256:8476.98:415:8475.55:101",
+        );
+        let valid = serde_json::from_str::<BookUpdate>(&valid).unwrap();
+        assert_eq!(
+            manager.handle_book_update(valid, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::Applied)
+        );
+
+        let corrupt = update(r#"[["8478","1","0","1"]]"#, "[]", 11, 12, b"");
+        let corrupt = serde_json::from_str::<BookUpdate>(&corrupt).unwrap();
+        let mismatch = manager
+            .handle_book_update(corrupt, BookUpdateType::Diff)
+            .unwrap_err();
+        assert_eq!(mismatch.seq_id, Some(12));
+        assert_eq!(mismatch.expected, 0);
+        assert_eq!(mismatch.computed, manager.book().checksum());
+        assert!(manager.needs_resync());
+
+        // dropped until resynced
+        let next = update("[]", "[]", 12, 13, b"");
+        let next = serde_json::from_str::<BookUpdate>(&next).unwrap();
+        assert_eq!(
+            manager.handle_book_update(next, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::Stale)
+        );
+
+        let books = Books {
+            inst_id: "BTC-USDT".into(),
+        };
+        let [unsubscribe, subscribe] = manager.resync(&books);
+        assert!(unsubscribe.contains(r#""op":"unsubscribe""#));
+        assert!(subscribe.contains(r#""op":"subscribe""#));
+        assert!(!manager.needs_resync());
+        assert_eq!(manager.last_seq, None);
+        assert_eq!(
+            manager.handle_book_update(snapshot, BookUpdateType::Snapshot),
+            Ok(BookUpdateOutcome::Applied)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_after_checksum_mismatch() {
+        let mut manager = BookManager::default();
+        let snapshot = update(
+            r#"[["8476.98","415","0","13"]]"#,
+            r#"[["8476.97","256","0","12"]]"#,
+            -1,
+            10,
+            b"8476.97:256:8476.98:415",
+        );
+        let snapshot = serde_json::from_str::<BookUpdate>(&snapshot).unwrap();
+        assert_eq!(
+            manager.handle_book_update(snapshot, BookUpdateType::Snapshot),
+            Ok(BookUpdateOutcome::Applied)
+        );
+        let corrupt = update(r#"[["8478","1","0","1"]]"#, "[]", 10, 11, b"");
+        let corrupt = serde_json::from_str::<BookUpdate>(&corrupt).unwrap();
+        assert!(manager
+            .handle_book_update(corrupt, BookUpdateType::Diff)
+            .is_err());
+        assert!(manager.needs_resync());
+
+        // pushed after resubscribing, without calling `resync`
+        let snapshot = update(
+            r#"[["8480","2","0","1"]]"#,
+            r#"[["8470","3","0","1"]]"#,
+            -1,
+            20,
+            b"8470:3:8480:2",
+        );
+        let snapshot = serde_json::from_str::<BookUpdate>(&snapshot).unwrap();
+        assert_eq!(
+            manager.handle_book_update(snapshot, BookUpdateType::Snapshot),
+            Ok(BookUpdateOutcome::Applied)
+        );
+        assert!(!manager.needs_resync());
+        assert!(manager.changes().reset);
+        assert_eq!(manager.last_seq, Some(20));
+        assert_eq!(manager.book().asks().count(), 1);
+
+        let next = update(
+            "[]",
+            r#"[["8471","1","0","1"]]"#,
+            20,
+            21,
+            b"8471:1:8480:2:8470:3",
+        );
+        let next = serde_json::from_str::<BookUpdate>(&next).unwrap();
+        assert_eq!(
+            manager.handle_book_update(next, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::Applied)
+        );
+    }
+
+    #[test]
+    fn test_invalid_level() {
+        let parse = |msg: &'static str| serde_json::from_str::<BookUpdate>(msg).unwrap();
+        let mut manager = BookManager::default();
+        let snapshot = parse(
+            r#"{"asks":[["101","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"1","prevSeqId":-1,"seqId":10}"#,
+        );
+        manager
+            .handle_book_update(snapshot, BookUpdateType::Snapshot)
+            .unwrap();
+        let checksum = manager.book().checksum();
+
+        // no level of the update is applied
+        let invalid = parse(
+            r#"{"asks":[["102","2","0","1"]],"bids":[["98","x","0","1"]],"ts":"2","prevSeqId":10,"seqId":11}"#,
+        );
+        assert_eq!(
+            manager.handle_book_update(invalid, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::InvalidLevel)
+        );
+        assert_eq!(manager.book().checksum(), checksum);
+        assert!(manager.changes().is_empty());
+        assert_eq!(manager.last_seq, Some(10));
+        let next =
+            parse(r#"{"asks":[],"bids":[["97","1","0","1"]],"ts":"3","prevSeqId":11,"seqId":12}"#);
+        assert_eq!(
+            manager.handle_book_update(next, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::GapDetected {
+                expected: 10,
+                prev_seq_id: 11
+            })
+        );
+
+        // `books5` pushes carry no checksum or `prevSeqId`, the book is left as it was
+        let mut manager = BookManager::default();
+        let books5 =
+            parse(r#"{"asks":[["101","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"5"}"#);
+        manager
+            .handle_book_update(books5, BookUpdateType::Snapshot)
+            .unwrap();
+        let invalid = parse(r#"{"asks":[["","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"6"}"#);
+        assert_eq!(
+            manager.handle_book_update(invalid, BookUpdateType::Snapshot),
+            Ok(BookUpdateOutcome::InvalidLevel)
+        );
+        assert_eq!(manager.book().asks().count(), 1);
+        assert_eq!(manager.last_exch_ts, Some(5));
+    }
+
+    #[test]
+    fn test_sequencing() {
+        use BookUpdateOutcome::*;
+
+        let mut manager = BookManager::default();
+        let mut handle = |prev_seq_id: i64, seq_id: i64, update_type: BookUpdateType| {
+            let asks = format!(r#"[["{}","1","0","1"]]"#, 100 + seq_id);
+            let update = format!(
+                r#"{{"asks":{asks},"bids":[["99","1","0","1"]],"ts":"{seq_id}","prevSeqId":{prev_seq_id},"seqId":{seq_id}}}"#
+            );
+            let update = serde_json::from_str::<BookUpdate>(&update).unwrap();
+            manager.handle_book_update(update, update_type).unwrap()
+        };
+        // first book update has to be snapshot
+        assert_eq!(handle(9, 10, BookUpdateType::Diff), Stale);
+        assert_eq!(handle(-1, 10, BookUpdateType::Snapshot), Applied);
+        assert_eq!(handle(10, 11, BookUpdateType::Diff), Applied);
+        assert_eq!(handle(10, 11, BookUpdateType::Diff), Duplicate);
+        assert_eq!(handle(9, 10, BookUpdateType::Diff), Stale);
+        assert_eq!(
+            handle(12, 13, BookUpdateType::Diff),
+            GapDetected {
+                expected: 11,
+                prev_seq_id: 12
+            }
+        );
+        // sequence reset due to maintenance
+        assert_eq!(handle(11, 3, BookUpdateType::Diff), Reset);
+        assert_eq!(handle(3, 4, BookUpdateType::Diff), Applied);
+        assert_eq!(handle(-1, 1, BookUpdateType::Snapshot), Reset);
+        assert_eq!(handle(1, 2, BookUpdateType::Diff), Applied);
+        assert_eq!(manager.last_seq, Some(2));
+        assert_eq!(manager.last_exch_ts, Some(2));
+
+        // `books5` style updates without `prevSeqId` or `seqId`
+        let mut manager = BookManager::default();
+        let books5 = r#"{"asks":[["101","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"5"}"#;
+        let update = serde_json::from_str::<BookUpdate>(books5).unwrap();
+        assert_eq!(
+            manager.handle_book_update(update, BookUpdateType::Snapshot),
+            Ok(Applied)
+        );
+        let next = books5.replace(r#""ts":"5""#, r#""ts":"6""#);
+        let update = serde_json::from_str::<BookUpdate>(&next).unwrap();
+        assert_eq!(
+            manager.handle_book_update(update, BookUpdateType::Snapshot),
+            Ok(Applied)
+        );
+        let stale = books5.replace(r#""ts":"5""#, r#""ts":"4""#);
+        let update = serde_json::from_str::<BookUpdate>(&stale).unwrap();
+        assert_eq!(
+            manager.handle_book_update(update, BookUpdateType::Snapshot),
+            Ok(Stale)
+        );
+        assert_eq!(manager.last_exch_ts, Some(6));
+    }
+
+    #[test]
+    fn test_changes_and_bbo_subscribers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use rust_decimal::Decimal;
+
+        use crate::api::v5::Side;
+        use crate::book::events::{LevelChange, LevelEvent};
+
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+        let parse = |msg: &'static str| serde_json::from_str::<BookUpdate>(msg).unwrap();
+
+        let mut manager = BookManager::default();
+        let bbo_rx = manager.subscribe_bbo();
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = notified.clone();
+        manager.on_bbo(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let snapshot = parse(
+            r#"{"asks":[["101","1","0","1"],["102","1","0","1"]],"bids":[["99","2","0","1"]],"ts":"1","prevSeqId":-1,"seqId":1}"#,
+        );
+        manager
+            .handle_book_update(snapshot, BookUpdateType::Snapshot)
+            .unwrap();
+        let changes = manager.changes();
+        assert!(changes.reset);
+        assert_eq!(changes.levels.len(), 3);
+        assert!(changes
+            .levels
+            .iter()
+            .all(|level| level.change == LevelChange::Insert));
+        let bbo = bbo_rx.try_recv().unwrap();
+        assert_eq!(bbo.bid, Some((dec("99"), dec("2"))));
+        assert_eq!(bbo.ask, Some((dec("101"), dec("1"))));
+        assert_eq!(bbo.seq_id, Some(1));
+
+        // below the top of book
+        let deep = parse(
+            r#"{"asks":[["102","3","0","1"]],"bids":[["98","1","0","1"]],"ts":"2","prevSeqId":1,"seqId":2}"#,
+        );
+        manager
+            .handle_book_update(deep, BookUpdateType::Diff)
+            .unwrap();
+        let changes = manager.changes();
+        assert!(!changes.reset && !changes.bbo_changed());
+        assert_eq!(
+            changes.levels,
+            [
+                LevelEvent {
+                    side: Side::Buy,
+                    price: dec("98"),
+                    size: dec("1"),
+                    orders: 1,
+                    prev_size: Decimal::ZERO,
+                    prev_orders: 0,
+                    change: LevelChange::Insert,
+                },
+                LevelEvent {
+                    side: Side::Sell,
+                    price: dec("102"),
+                    size: dec("3"),
+                    orders: 1,
+                    prev_size: dec("1"),
+                    prev_orders: 1,
+                    change: LevelChange::Update,
+                },
+            ]
+        );
+        assert!(bbo_rx.try_recv().is_err());
+
+        let best_ask_removed =
+            parse(r#"{"asks":[["101","0","0","0"]],"bids":[],"ts":"3","prevSeqId":2,"seqId":3}"#);
+        manager
+            .handle_book_update(best_ask_removed, BookUpdateType::Diff)
+            .unwrap();
+        let changes = manager.changes();
+        assert!(changes.ask_changed && !changes.bid_changed);
+        assert_eq!(changes.levels[0].change, LevelChange::Remove);
+        assert_eq!(bbo_rx.try_recv().unwrap().ask, Some((dec("102"), dec("3"))));
+
+        // `bbo-tbt` implies the levels behind the best bid are gone
+        let bbo = parse(
+            r#"{"asks":[["102","3","0","1"]],"bids":[["97","1","0","1"]],"ts":"4","seqId":4}"#,
+        );
+        manager
+            .handle_book_update(bbo, BookUpdateType::BBO)
+            .unwrap();
+        let removed = manager
+            .changes()
+            .levels
+            .iter()
+            .filter(|level| level.change == LevelChange::Remove)
+            .map(|level| level.price)
+            .collect::<Vec<_>>();
+        assert_eq!(removed, [dec("99"), dec("98")]);
+        assert!(manager.changes().bid_changed);
+
+        // dropped updates change nothing
+        let duplicate = parse(r#"{"asks":[],"bids":[],"ts":"4","seqId":4}"#);
+        manager
+            .handle_book_update(duplicate, BookUpdateType::Diff)
+            .unwrap();
+        assert!(manager.changes().is_empty());
+        assert_eq!(notified.load(Ordering::Relaxed), 3);
+        drop(bbo_rx);
     }
 }
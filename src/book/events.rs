@@ -0,0 +1,110 @@
+//! What an update changed in a book, see [`BookManager::changes`](crate::book::book_manager::BookManager::changes).
+
+use std::fmt::{Debug, Formatter};
+use std::sync::mpsc;
+
+use crate::api::v5::Side;
+use crate::book::Fixed;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelChange {
+    Insert,
+    Update,
+    Remove,
+}
+
+/// Level changed by an update, `size` is the new size and zero for removed levels.
+/// `prev_size` and `prev_orders` are the level before the update, zero for inserted levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelEvent {
+    pub side: Side,
+    pub price: Fixed,
+    pub size: Fixed,
+    /// Number of orders, zero when unknown
+    pub orders: u32,
+    pub prev_size: Fixed,
+    pub prev_orders: u32,
+    pub change: LevelChange,
+}
+
+/// Changes of the last update handled.
+/// A snapshot replaces the book, its levels are reported as inserts with `reset` set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookChanges {
+    pub levels: Vec<LevelEvent>,
+    pub reset: bool,
+    /// Best bid price or size changed
+    pub bid_changed: bool,
+    /// Best ask price or size changed
+    pub ask_changed: bool,
+}
+
+impl BookChanges {
+    #[inline]
+    pub fn bbo_changed(&self) -> bool {
+        self.bid_changed || self.ask_changed
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.levels.clear();
+        self.reset = false;
+        self.bid_changed = false;
+        self.ask_changed = false;
+    }
+}
+
+/// Top of book after an update changed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bbo {
+    /// Best bid `(price, size)`
+    pub bid: Option<(Fixed, Fixed)>,
+    /// Best ask `(price, size)`
+    pub ask: Option<(Fixed, Fixed)>,
+    pub seq_id: Option<i64>,
+    pub ts: Option<u64>,
+}
+
+type BboCallback = Box<dyn FnMut(&Bbo) + Send>;
+
+/// Callbacks notified of top of book changes
+#[derive(Default)]
+pub(crate) struct BboSubscribers(Vec<BboCallback>);
+
+impl BboSubscribers {
+    pub(crate) fn push(&mut self, callback: BboCallback) {
+        self.0.push(callback);
+    }
+
+    /// Sender of a channel subscriber, dropped once its receiver is
+    pub(crate) fn channel(&mut self) -> mpsc::Receiver<Bbo> {
+        let (sender, receiver) = mpsc::channel();
+        let mut sender = Some(sender);
+        self.push(Box::new(move |bbo| {
+            if sender.as_ref().is_some_and(|tx| tx.send(*bbo).is_err()) {
+                sender = None;
+            }
+        }));
+        receiver
+    }
+
+    pub(crate) fn notify(&mut self, bbo: &Bbo) {
+        for callback in self.0.iter_mut() {
+            callback(bbo);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for BboSubscribers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BboSubscribers({})", self.0.len())
+    }
+}
@@ -0,0 +1,315 @@
+//! Expected execution of market and IOC orders against an [`OrderBook`].
+//!
+//! `side` is the side of the order: a buy walks the asks and a sell walks the bids.
+//! Sizes are in the unit of the book, i.e. contracts for derivatives, and quote amounts are
+//! `price × size`, without the contract value applied. [`FillSize::Notional`] applies `ctVal`.
+
+use rust_decimal::Decimal;
+
+use crate::api::v5::{ContractType, Side};
+use crate::book::{Fixed, OrderBook, BPS};
+
+/// Margin in USDT, or USD for inverse contracts, behind the impact notional of OKX funding rates
+pub const IMPACT_MARGIN: Fixed = Decimal::from_parts(200, 0, 0, false, 0);
+
+/// Amount of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillSize {
+    /// Size in the unit of the book
+    Base(Fixed),
+    /// Quote amount, `price × size`
+    Quote(Fixed),
+    /// Notional of contracts worth `ct_val` each, see `Instrument::face_value`.
+    /// A linear contract is worth `price × ct_val` in the quote currency, e.g. USDT for BTC-USDT-SWAP,
+    /// and an inverse contract `ct_val` in USD, e.g. BTC-USD-SWAP
+    Notional {
+        notional: Fixed,
+        ct_val: Fixed,
+        ct_type: ContractType,
+    },
+}
+
+impl FillSize {
+    fn target(&self) -> Fixed {
+        match *self {
+            FillSize::Base(target) | FillSize::Quote(target) => target,
+            FillSize::Notional { notional, .. } => notional,
+        }
+    }
+
+    /// Amount of one unit of size at `price`, in the unit of this amount
+    fn unit(&self, price: Fixed) -> Fixed {
+        match *self {
+            FillSize::Base(_) => Decimal::ONE,
+            FillSize::Quote(_) => price,
+            FillSize::Notional {
+                ct_val,
+                ct_type: ContractType::Linear,
+                ..
+            } => price * ct_val,
+            FillSize::Notional {
+                ct_val,
+                ct_type: ContractType::Inverse,
+                ..
+            } => ct_val,
+        }
+    }
+}
+
+/// Result of walking the book for an order, see [`OrderBook::fill_estimate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FillEstimate {
+    /// Average fill price
+    pub vwap: Fixed,
+    /// Price of the last level reached
+    pub worst_price: Fixed,
+    /// Size filled
+    pub filled: Fixed,
+    /// Quote amount filled
+    pub notional: Fixed,
+    /// Whether the book holds enough size to fill the whole order
+    pub complete: bool,
+}
+
+impl OrderBook {
+    /// Walks the opposite side of the book for an order of `side` and `size`.
+    /// `None` if the opposite side is empty or `size` is not positive.
+    pub fn fill_estimate(&self, side: Side, size: FillSize) -> Option<FillEstimate> {
+        match side {
+            Side::Buy => walk(self.asks(), size),
+            Side::Sell => walk(self.bids(), size),
+        }
+    }
+
+    /// Average fill price of an order, `None` if the book cannot fill it completely
+    pub fn vwap(&self, side: Side, size: FillSize) -> Option<Fixed> {
+        let estimate = self.fill_estimate(side, size)?;
+        estimate.complete.then_some(estimate.vwap)
+    }
+
+    /// Price of the last level an order reaches, `None` if the book cannot fill it completely
+    pub fn worst_price(&self, side: Side, size: FillSize) -> Option<Fixed> {
+        let estimate = self.fill_estimate(side, size)?;
+        estimate.complete.then_some(estimate.worst_price)
+    }
+
+    /// Cost of an order against mid in basis points, positive when filling worse than mid
+    pub fn slippage_bps(&self, side: Side, size: FillSize) -> Option<Fixed> {
+        let vwap = self.vwap(side, size)?;
+        let mid = self.mid()?;
+        let slippage = match side {
+            Side::Buy => vwap - mid,
+            Side::Sell => mid - vwap,
+        };
+        (slippage * BPS).checked_div(mid)
+    }
+
+    /// Size available to an order of `side` within `bps` basis points of mid
+    pub fn size_within_bps(&self, side: Side, bps: Fixed) -> Fixed {
+        let Some(mid) = self.mid() else {
+            return Decimal::ZERO;
+        };
+        let offset = mid * bps / BPS;
+        match side {
+            Side::Buy => self.size_to_price(Side::Sell, mid + offset),
+            Side::Sell => self.size_to_price(Side::Buy, mid - offset),
+        }
+    }
+
+    /// Impact bid and ask prices as used by OKX funding rates: the average fill prices of selling
+    /// and buying `impact_notional` of contracts worth `ct_val`, see [`impact_notional`] and [`FillSize::Notional`]
+    pub fn impact_prices(
+        &self,
+        impact_notional: Fixed,
+        ct_val: Fixed,
+        ct_type: ContractType,
+    ) -> Option<(Fixed, Fixed)> {
+        let size = FillSize::Notional {
+            notional: impact_notional,
+            ct_val,
+            ct_type,
+        };
+        Some((self.vwap(Side::Sell, size)?, self.vwap(Side::Buy, size)?))
+    }
+}
+
+/// Impact margin notional of an instrument, [`IMPACT_MARGIN`] over the initial margin rate at `max_leverage`
+pub fn impact_notional(max_leverage: Fixed) -> Fixed {
+    IMPACT_MARGIN * max_leverage
+}
+
+fn walk(levels: impl Iterator<Item = (Fixed, Fixed)>, size: FillSize) -> Option<FillEstimate> {
+    let target = size.target();
+    if target <= Decimal::ZERO {
+        return None;
+    }
+    let (mut filled, mut notional) = (Decimal::ZERO, Decimal::ZERO);
+    // filled in the unit of `size`
+    let mut spent = Decimal::ZERO;
+    let mut worst_price = None;
+    let mut complete = false;
+    for (price, level_sz) in levels {
+        let unit = size.unit(price);
+        if unit <= Decimal::ZERO {
+            return None;
+        }
+        let remaining = target - spent;
+        let (take, done) = if remaining <= unit * level_sz {
+            (remaining / unit, true)
+        } else {
+            (level_sz, false)
+        };
+        filled += take;
+        notional += take * price;
+        spent += take * unit;
+        worst_price = Some(price);
+        if done {
+            complete = true;
+            break;
+        }
+    }
+    Some(FillEstimate {
+        vwap: notional.checked_div(filled)?,
+        worst_price: worst_price?,
+        filled,
+        notional,
+        complete,
+    })
+}
+
+#[cfg(test)]
+mod tests_execution {
+    use rust_decimal::Decimal;
+
+    use super::{impact_notional, FillSize};
+    use crate::api::v5::{ContractType, Side};
+    use crate::book::OrderBook;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn order_book() -> OrderBook {
+        let mut book = OrderBook::default();
+        for (price, size) in [("100", "1"), ("99.5", "3"), ("99", "2")] {
+            book.handle_level(dec(price), dec(size), Side::Buy, false);
+        }
+        for (price, size) in [("100.5", "3"), ("101", "4")] {
+            book.handle_level(dec(price), dec(size), Side::Sell, false);
+        }
+        book
+    }
+
+    #[test]
+    fn test_fill_estimate() {
+        let book = order_book();
+        // 3 @ 100.5 + 2 @ 101
+        let buy = book
+            .fill_estimate(Side::Buy, FillSize::Base(dec("5")))
+            .unwrap();
+        assert_eq!(buy.vwap, dec("100.7"));
+        assert_eq!(buy.worst_price, dec("101"));
+        assert_eq!(buy.notional, dec("503.5"));
+        assert!(buy.complete);
+        assert_eq!(
+            book.vwap(Side::Buy, FillSize::Quote(dec("503.5"))),
+            Some(dec("100.7"))
+        );
+        assert_eq!(
+            book.worst_price(Side::Buy, FillSize::Quote(dec("100"))),
+            Some(dec("100.5"))
+        );
+
+        let sell = book
+            .fill_estimate(Side::Sell, FillSize::Base(dec("10")))
+            .unwrap();
+        assert_eq!(sell.filled, dec("6"));
+        assert_eq!(sell.worst_price, dec("99"));
+        assert!(!sell.complete);
+        assert_eq!(book.vwap(Side::Sell, FillSize::Base(dec("10"))), None);
+        assert_eq!(
+            book.fill_estimate(Side::Sell, FillSize::Base(Decimal::ZERO)),
+            None
+        );
+
+        // mid 100.25
+        assert_eq!(
+            book.slippage_bps(Side::Buy, FillSize::Base(dec("5")))
+                .unwrap()
+                .round_dp(2),
+            dec("44.89")
+        );
+        assert_eq!(
+            book.slippage_bps(Side::Sell, FillSize::Base(dec("1")))
+                .unwrap()
+                .round_dp(4),
+            dec("24.9377")
+        );
+    }
+
+    #[test]
+    fn test_size_within_bps_and_impact_prices() {
+        let book = order_book();
+        // mid 100.25, 50 bps reach 100.75125 and 99.74875
+        assert_eq!(book.size_within_bps(Side::Buy, dec("50")), dec("3"));
+        assert_eq!(book.size_within_bps(Side::Sell, dec("50")), dec("1"));
+        assert_eq!(book.size_within_bps(Side::Sell, dec("100")), dec("4"));
+        assert_eq!(
+            OrderBook::default().size_within_bps(Side::Buy, dec("50")),
+            Decimal::ZERO
+        );
+
+        assert_eq!(impact_notional(dec("125")), dec("25000"));
+        let (impact_bid, impact_ask) = book
+            .impact_prices(dec("200"), Decimal::ONE, ContractType::Linear)
+            .unwrap();
+        // 1 @ 100 and 100 / 99.5 @ 99.5
+        assert_eq!(impact_bid.round_dp(6), dec("99.749373"));
+        assert_eq!(impact_ask, dec("100.5"));
+        assert_eq!(
+            book.impact_prices(dec("100000"), Decimal::ONE, ContractType::Linear),
+            None
+        );
+    }
+
+    #[test]
+    fn test_impact_prices_contract_value() {
+        let mut book = OrderBook::default();
+        for (price, size) in [("50000", "30"), ("40000", "300")] {
+            book.handle_level(dec(price), dec(size), Side::Buy, false);
+        }
+        for (price, size) in [("50010", "100"), ("60000", "200")] {
+            book.handle_level(dec(price), dec(size), Side::Sell, false);
+        }
+
+        // BTC-USDT-SWAP, 0.01 BTC per contract: 30 @ 50000 fill 15000 USDT, 25 @ 40000 the other 10000
+        let notional = impact_notional(dec("125"));
+        let (impact_bid, impact_ask) = book
+            .impact_prices(notional, dec("0.01"), ContractType::Linear)
+            .unwrap();
+        assert_eq!(impact_bid.round_dp(4), dec("45454.5455"));
+        assert_eq!(impact_ask, dec("50010"));
+        let sell = FillSize::Notional {
+            notional,
+            ct_val: dec("0.01"),
+            ct_type: ContractType::Linear,
+        };
+        assert_eq!(
+            book.fill_estimate(Side::Sell, sell).unwrap().filled,
+            dec("55")
+        );
+
+        // BTC-USD-SWAP, 100 USD per contract: 250 contracts whatever the price
+        let (impact_bid, impact_ask) = book
+            .impact_prices(notional, dec("100"), ContractType::Inverse)
+            .unwrap();
+        // 30 @ 50000 and 220 @ 40000
+        assert_eq!(impact_bid, dec("41200"));
+        // 100 @ 50010 and 150 @ 60000
+        assert_eq!(impact_ask, dec("56004"));
+        assert_eq!(
+            book.impact_prices(notional, Decimal::ZERO, ContractType::Linear),
+            None
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Integer fixed-point book, an alternative to the `Decimal` / `BTreeMap` [`OrderBook`](crate::book::OrderBook).
+//!
+//! Prices are counted in ticks of the instrument's `tickSz` and sizes in lots of its `lotSz`,
+//! parsed straight from the pushed strings by [`FixedScale::parse`]. [`FixedBook`] keeps each side
+//! in a sorted `Vec` with the best level last, where most updates land.
+//!
+//! ```
+//! use okx_rs::api::v5::Side;
+//! use okx_rs::book::fixed::{FixedBook, FixedScale};
+//!
+//! let price = FixedScale::new("0.1").unwrap();
+//! let size = FixedScale::new("0.0001").unwrap();
+//! let mut book = FixedBook::default();
+//! book.handle_level(price.parse("42000.1").unwrap(), size.parse("1.5").unwrap(), Side::Sell);
+//! let best_ask = book.best_ask().unwrap();
+//! assert_eq!(price.to_decimal(best_ask.price).unwrap().to_string(), "42000.1");
+//! assert_eq!(best_ask.size, 15_000);
+//! ```
+
+use rust_decimal::Decimal;
+
+use crate::api::v5::{BookUpdate, Instrument, Level, Side};
+
+/// Largest number of decimals of a scale, keeps scaled values within `i64`
+const MAX_DECIMALS: u32 = 18;
+
+/// Step of a fixed-point value, e.g. `tickSz` for prices or `lotSz` for sizes.
+/// Values are integers counting steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedScale {
+    /// Decimals of the step
+    decimals: u32,
+    /// Step in units of `10^-decimals`
+    step: i64,
+}
+
+impl FixedScale {
+    /// Scale of a step given as a decimal string, e.g. `"0.01"`. `None` if the step is not positive.
+    pub fn new(step: &str) -> Option<Self> {
+        let (mantissa, decimals) = parse_decimal(step)?;
+        (mantissa > 0 && decimals <= MAX_DECIMALS).then_some(Self {
+            decimals,
+            step: mantissa,
+        })
+    }
+
+    /// Scale of a step parsed as a float, e.g. [`Instrument::tick_size`]
+    pub fn from_f64(step: f64) -> Option<Self> {
+        // `Display` of `f64` is the shortest representation that parses back to the same value
+        Self::new(&step.to_string())
+    }
+
+    /// Number of steps in `value`, without allocating.
+    /// `None` if `value` is not a decimal number, overflows, or is not a multiple of the step.
+    pub fn parse(&self, value: &str) -> Option<i64> {
+        let (mantissa, decimals) = parse_decimal(value)?;
+        let scaled = if decimals <= self.decimals {
+            mantissa.checked_mul(10i64.checked_pow(self.decimals - decimals)?)?
+        } else {
+            // digits past the step's decimals must be trailing zeros
+            let divisor = 10i64.checked_pow(decimals - self.decimals)?;
+            if mantissa % divisor != 0 {
+                return None;
+            }
+            mantissa / divisor
+        };
+        (scaled % self.step == 0).then_some(scaled / self.step)
+    }
+
+    /// Value of `steps` as a `Decimal`, `None` if it does not fit its 96 bit mantissa
+    pub fn to_decimal(&self, steps: i64) -> Option<Decimal> {
+        let scaled = steps as i128 * self.step as i128;
+        Decimal::try_from_i128_with_scale(scaled, self.decimals).ok()
+    }
+
+    /// Value of `steps` as a float
+    pub fn to_f64(&self, steps: i64) -> f64 {
+        // `steps * step` may not fit in `i64` for large sizes on small steps
+        (steps as i128 * self.step as i128) as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// Mantissa and number of decimals of an unsigned decimal string, e.g. `"12.50"` is `(1250, 2)`
+fn parse_decimal(value: &str) -> Option<(i64, u32)> {
+    let bytes = value.as_bytes();
+    let mut mantissa: i64 = 0;
+    let mut decimals = None;
+    let mut digits = 0;
+    for &byte in bytes {
+        match byte {
+            b'0'..=b'9' => {
+                digits += 1;
+                mantissa = mantissa
+                    .checked_mul(10)?
+                    .checked_add((byte - b'0') as i64)?;
+                if let Some(decimals) = decimals.as_mut() {
+                    *decimals += 1;
+                }
+            }
+            b'.' if decimals.is_none() => decimals = Some(0),
+            _ => return None,
+        }
+    }
+    (digits > 0).then_some((mantissa, decimals.unwrap_or(0)))
+}
+
+/// Price and size scales of an instrument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstrumentScales {
+    pub price: FixedScale,
+    pub size: FixedScale,
+}
+
+impl InstrumentScales {
+    /// Scales from `tickSz` and `lotSz`, `None` if either is missing
+    pub fn from_instrument(instrument: &Instrument) -> Option<Self> {
+        Some(Self {
+            price: FixedScale::from_f64(instrument.tick_size?)?,
+            size: FixedScale::from_f64(instrument.lot_size?)?,
+        })
+    }
+
+    /// Price and size of a pushed level, in ticks and lots
+    #[inline]
+    pub fn parse_level(&self, level: &Level) -> Option<FixedLevel> {
+        Some(FixedLevel {
+            price: self.price.parse(level.price)?,
+            size: self.size.parse(level.size)?,
+        })
+    }
+}
+
+/// Price in ticks and size in lots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedLevel {
+    pub price: i64,
+    pub size: i64,
+}
+
+/// Order book of fixed-point levels.
+/// Each side is sorted so that the best level is last: bids ascending and asks descending.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixedBook {
+    bids: Vec<FixedLevel>,
+    asks: Vec<FixedLevel>,
+}
+
+impl FixedBook {
+    /// Sets the size of a level, a size of zero removes it
+    pub fn handle_level(&mut self, price: i64, size: i64, side: Side) {
+        let (levels, position) = match side {
+            Side::Buy => {
+                let position = self.bids.binary_search_by(|level| level.price.cmp(&price));
+                (&mut self.bids, position)
+            }
+            Side::Sell => {
+                let position = self.asks.binary_search_by(|level| price.cmp(&level.price));
+                (&mut self.asks, position)
+            }
+        };
+        match (position, size > 0) {
+            (Ok(index), true) => levels[index].size = size,
+            (Ok(index), false) => {
+                levels.remove(index);
+            }
+            (Err(index), true) => levels.insert(index, FixedLevel { price, size }),
+            (Err(_), false) => {}
+        }
+    }
+
+    /// Applies the levels of a `books` push, clearing the book first for a snapshot.
+    /// Returns `false` if some levels could not be parsed with `scales`, those are skipped.
+    pub fn apply(
+        &mut self,
+        scales: &InstrumentScales,
+        update: &BookUpdate,
+        snapshot: bool,
+    ) -> bool {
+        if snapshot {
+            self.clear();
+        }
+        let mut parsed = true;
+        for (levels, side) in [(&update.bids, Side::Buy), (&update.asks, Side::Sell)] {
+            for level in levels.iter() {
+                match scales.parse_level(level) {
+                    Some(level) => self.handle_level(level.price, level.size, side),
+                    None => parsed = false,
+                }
+            }
+        }
+        parsed
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    #[inline]
+    pub fn best_bid(&self) -> Option<FixedLevel> {
+        self.bids.last().copied()
+    }
+
+    #[inline]
+    pub fn best_ask(&self) -> Option<FixedLevel> {
+        self.asks.last().copied()
+    }
+
+    /// Bid levels from the best price
+    pub fn bids(&self) -> impl DoubleEndedIterator<Item = &FixedLevel> + ExactSizeIterator {
+        self.bids.iter().rev()
+    }
+
+    /// Ask levels from the best price
+    pub fn asks(&self) -> impl DoubleEndedIterator<Item = &FixedLevel> + ExactSizeIterator {
+        self.asks.iter().rev()
+    }
+
+    pub fn crossed(&self) -> bool {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => bid.price > ask.price,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_fixed {
+    use rust_decimal::Decimal;
+
+    use super::{FixedBook, FixedLevel, FixedScale, InstrumentScales};
+    use crate::api::v5::{BookUpdate, Side};
+
+    #[test]
+    fn test_fixed_scale() {
+        let tick = FixedScale::new("0.5").unwrap();
+        assert_eq!(tick.parse("100.5"), Some(201));
+        assert_eq!(tick.parse("100"), Some(200));
+        assert_eq!(tick.parse("100.50"), Some(201));
+        // off the tick grid
+        assert_eq!(tick.parse("100.25"), None);
+        assert_eq!(tick.to_decimal(201).unwrap().to_string(), "100.5");
+
+        let lot = FixedScale::from_f64(0.0001).unwrap();
+        assert_eq!(lot.parse("0.0001"), Some(1));
+        assert_eq!(lot.parse("12"), Some(120_000));
+        assert_eq!(lot.parse(".5"), Some(5_000));
+        assert_eq!(lot.to_f64(15_000), 1.5);
+        for invalid in ["", ".", "1.2.3", "-1", "1e5", "99999999999999999999"] {
+            assert_eq!(lot.parse(invalid), None, "{invalid}");
+        }
+        assert_eq!(FixedScale::new("0"), None);
+
+        // `steps * step` overflows `i64`
+        let step = FixedScale::new("0.000000000000000003").unwrap();
+        assert_eq!(step.to_f64(i64::MAX), i64::MAX as f64 * 3e-18);
+        assert_eq!(
+            step.to_decimal(i64::MAX).unwrap(),
+            Decimal::from_i128_with_scale(i64::MAX as i128 * 3, 18)
+        );
+        let step = FixedScale::new("9223372036854775807").unwrap();
+        assert_eq!(step.to_decimal(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_fixed_book() {
+        let scales = InstrumentScales {
+            price: FixedScale::new("0.1").unwrap(),
+            size: FixedScale::new("1").unwrap(),
+        };
+        let snapshot = r#"{"asks":[["100.1","3","0","1"],["100.3","1","0","1"]],"bids":[["99.9","2","0","1"],["99.5","4","0","1"]],"ts":"1"}"#;
+        let snapshot = serde_json::from_str::<BookUpdate>(snapshot).unwrap();
+        let mut book = FixedBook::default();
+        assert!(book.apply(&scales, &snapshot, true));
+        assert_eq!(
+            book.best_bid(),
+            Some(FixedLevel {
+                price: 999,
+                size: 2
+            })
+        );
+        assert_eq!(book.best_ask().unwrap().price, 1001);
+
+        let update = r#"{"asks":[["100.1","0","0","0"],["100.2","5","0","1"]],"bids":[["99.9","7","0","1"],["99.95","1","0","1"]],"ts":"2"}"#;
+        let update = serde_json::from_str::<BookUpdate>(update).unwrap();
+        // 99.95 is off the tick grid
+        assert!(!book.apply(&scales, &update, false));
+        assert_eq!(
+            book.asks().map(|level| level.price).collect::<Vec<_>>(),
+            [1002, 1003]
+        );
+        assert_eq!(
+            book.bids()
+                .map(|level| (level.price, level.size))
+                .collect::<Vec<_>>(),
+            [(999, 7), (995, 4)]
+        );
+        assert!(!book.crossed());
+
+        book.handle_level(1004, 1, Side::Buy);
+        assert!(book.crossed());
+        assert!(book.apply(&scales, &snapshot, true));
+        assert_eq!(book.bids().len(), 2);
+    }
+}
@@ -1,21 +1,33 @@
 use std::cmp::Reverse;
+use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
 
 use rust_decimal::Decimal;
 
 use crate::api::v5::Side;
+use crate::book::events::{LevelChange, LevelEvent};
+use crate::websocket::checksum::book_checksum;
 
+pub mod aggregate;
 pub mod book_manager;
+pub mod events;
+pub mod execution;
+pub mod fixed;
+pub mod registry;
+pub mod signals;
+pub mod snapshot;
 type Fixed = Decimal;
 
 #[derive(Debug)]
 pub struct PartialLevel {
     size: Fixed,
+    /// Number of orders, zero when unknown
+    orders: u32,
 }
 impl From<Fixed> for PartialLevel {
     fn from(size: Fixed) -> Self {
-        Self { size }
+        Self { size, orders: 0 }
     }
 }
 
@@ -51,54 +63,316 @@ impl std::fmt::Debug for OrderBook {
 
 impl OrderBook {
     pub fn handle_level(&mut self, price: Fixed, size: Fixed, side: Side, bbo: bool) {
-        if size <= Decimal::ZERO {
-            self.remove_level(price, side);
+        self.apply_level(price, size, 0, side, bbo, None);
+    }
+
+    /// [`OrderBook::handle_level`] with the number of orders of the level, see `Level::orders`
+    pub fn handle_level_with_orders(
+        &mut self,
+        price: Fixed,
+        size: Fixed,
+        orders: u32,
+        side: Side,
+        bbo: bool,
+    ) {
+        self.apply_level(price, size, orders, side, bbo, None);
+    }
+
+    /// [`OrderBook::handle_level`] recording the levels changed into `events`
+    pub(crate) fn apply_level(
+        &mut self,
+        price: Fixed,
+        size: Fixed,
+        orders: u32,
+        side: Side,
+        bbo: bool,
+        mut events: Option<&mut Vec<LevelEvent>>,
+    ) {
+        let change = if size <= Decimal::ZERO {
+            self.remove_level(price, side)
+                .map(|prev| (LevelChange::Remove, Some(prev)))
         } else {
-            self.update_level(price, size, side);
+            Some(self.update_level(price, size, orders, side))
+        };
+        if let (Some((change, prev)), Some(events)) = (change, events.as_deref_mut()) {
+            let (prev_size, prev_orders) =
+                prev.map_or((Decimal::ZERO, 0), |prev| (prev.size, prev.orders));
+            events.push(LevelEvent {
+                side,
+                price,
+                size: size.max(Decimal::ZERO),
+                orders: if change == LevelChange::Remove {
+                    0
+                } else {
+                    orders
+                },
+                prev_size,
+                prev_orders,
+                change,
+            });
         }
 
         if bbo {
-            self.handle_bbo(price, size, side);
+            self.handle_bbo(price, size, side, events);
         }
     }
 
-    fn handle_bbo(&mut self, price: Fixed, _size: Fixed, side: Side) {
+    /// Removes levels behind the best price of a `bbo-tbt` push
+    fn handle_bbo(
+        &mut self,
+        price: Fixed,
+        _size: Fixed,
+        side: Side,
+        mut events: Option<&mut Vec<LevelEvent>>,
+    ) {
+        let mut removed = |price: Fixed, prev: &PartialLevel| {
+            if let Some(events) = events.as_deref_mut() {
+                events.push(LevelEvent {
+                    side,
+                    price,
+                    size: Decimal::ZERO,
+                    orders: 0,
+                    prev_size: prev.size,
+                    prev_orders: prev.orders,
+                    change: LevelChange::Remove,
+                });
+            }
+        };
         match side {
-            Side::Buy => self.bids.retain(|k, _v| k.0 <= price),
-            Side::Sell => self.asks.retain(|k, _v| *k >= price),
+            Side::Buy => self.bids.retain(|k, v| {
+                let keep = k.0 <= price;
+                if !keep {
+                    removed(k.0, v);
+                }
+                keep
+            }),
+            Side::Sell => self.asks.retain(|k, v| {
+                let keep = *k >= price;
+                if !keep {
+                    removed(*k, v);
+                }
+                keep
+            }),
         };
     }
 
-    fn update_level(&mut self, price: Fixed, size: Fixed, side: Side) {
-        let partial_level = match side {
-            Side::Buy => self
-                .bids
-                .entry(Reverse(price))
-                .or_insert_with(|| size.into()),
-            Side::Sell => self.asks.entry(price).or_insert_with(|| size.into()),
-        };
-        partial_level.size = size;
+    /// Sets a level, returns the level it replaced
+    fn update_level(
+        &mut self,
+        price: Fixed,
+        size: Fixed,
+        orders: u32,
+        side: Side,
+    ) -> (LevelChange, Option<PartialLevel>) {
+        fn set_level<K: Ord>(
+            entry: Entry<'_, K, PartialLevel>,
+            level: PartialLevel,
+        ) -> (LevelChange, Option<PartialLevel>) {
+            match entry {
+                Entry::Occupied(mut entry) => {
+                    let prev = std::mem::replace(entry.get_mut(), level);
+                    (LevelChange::Update, Some(prev))
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(level);
+                    (LevelChange::Insert, None)
+                }
+            }
+        }
+        let level = PartialLevel { size, orders };
+        match side {
+            Side::Buy => set_level(self.bids.entry(Reverse(price)), level),
+            Side::Sell => set_level(self.asks.entry(price), level),
+        }
     }
 
-    fn remove_level(&mut self, price: Fixed, side: Side) {
+    /// Level removed, if any
+    fn remove_level(&mut self, price: Fixed, side: Side) -> Option<PartialLevel> {
         match side {
             Side::Buy => self.bids.remove(&Reverse(price)),
             Side::Sell => self.asks.remove(&price),
-        };
+        }
+    }
+
+    /// Checksum of the top 25 levels, compared with `checksum` of `books` push data
+    pub fn checksum(&self) -> i32 {
+        book_checksum(self.bids(), self.asks())
     }
 
-    fn best_bid(&self) -> Option<(Fixed, Fixed)> {
+    /// Best bid `(price, size)`
+    pub fn best_bid(&self) -> Option<(Fixed, Fixed)> {
         self.bids.iter().next().map(|(k, v)| (k.0, v.size))
     }
 
-    fn best_ask(&self) -> Option<(Fixed, Fixed)> {
+    /// Best ask `(price, size)`
+    pub fn best_ask(&self) -> Option<(Fixed, Fixed)> {
         self.asks.iter().next().map(|(k, v)| (*k, v.size))
     }
 
-    fn crossed(&self) -> bool {
+    /// Best bid and best ask, `None` if either side is empty
+    pub fn bbo(&self) -> Option<((Fixed, Fixed), (Fixed, Fixed))> {
+        Some((self.best_bid()?, self.best_ask()?))
+    }
+
+    pub fn crossed(&self) -> bool {
         match (self.best_bid(), self.best_ask()) {
             (Some((bid, _)), Some((ask, _))) => bid > ask,
             _ => false,
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Number of levels on `side`
+    pub fn depth(&self, side: Side) -> usize {
+        match side {
+            Side::Buy => self.bids.len(),
+            Side::Sell => self.asks.len(),
+        }
+    }
+
+    /// Bid `(price, size)` levels from the best price
+    pub fn bids(&self) -> impl DoubleEndedIterator<Item = (Fixed, Fixed)> + ExactSizeIterator + '_ {
+        self.bids.iter().map(|(price, level)| (price.0, level.size))
+    }
+
+    /// Ask `(price, size)` levels from the best price
+    pub fn asks(&self) -> impl DoubleEndedIterator<Item = (Fixed, Fixed)> + ExactSizeIterator + '_ {
+        self.asks.iter().map(|(price, level)| (*price, level.size))
+    }
+
+    /// Bid `(price, size, orders)` levels from the best price, orders are zero when unknown
+    pub fn bids_with_orders(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (Fixed, Fixed, u32)> + ExactSizeIterator + '_ {
+        self.bids
+            .iter()
+            .map(|(price, level)| (price.0, level.size, level.orders))
+    }
+
+    /// Ask `(price, size, orders)` levels from the best price, orders are zero when unknown
+    pub fn asks_with_orders(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (Fixed, Fixed, u32)> + ExactSizeIterator + '_ {
+        self.asks
+            .iter()
+            .map(|(price, level)| (*price, level.size, level.orders))
+    }
+
+    pub fn mid(&self) -> Option<Fixed> {
+        let ((bid, _), (ask, _)) = self.bbo()?;
+        Some((bid + ask) / Decimal::TWO)
+    }
+
+    /// Best ask minus best bid
+    pub fn spread(&self) -> Option<Fixed> {
+        let ((bid, _), (ask, _)) = self.bbo()?;
+        Some(ask - bid)
+    }
+
+    /// Spread in multiples of `tick_sz`, see `Instrument::tick_sz`
+    pub fn spread_ticks(&self, tick_sz: Fixed) -> Option<Fixed> {
+        self.spread()?.checked_div(tick_sz)
+    }
+
+    /// Spread in basis points of mid
+    pub fn spread_bps(&self) -> Option<Fixed> {
+        (self.spread()? * BPS).checked_div(self.mid()?)
+    }
+
+    /// Mid weighted by the size on the opposite side, leaning towards the side more likely to trade through
+    pub fn microprice(&self) -> Option<Fixed> {
+        let ((bid, bid_sz), (ask, ask_sz)) = self.bbo()?;
+        (bid * ask_sz + ask * bid_sz).checked_div(bid_sz + ask_sz)
+    }
+
+    /// Total size on `side` at `price` or better, i.e. bids at or above and asks at or below `price`
+    pub fn size_to_price(&self, side: Side, price: Fixed) -> Fixed {
+        match side {
+            Side::Buy => self
+                .bids
+                .range(..=Reverse(price))
+                .map(|(_, level)| level.size)
+                .sum(),
+            Side::Sell => self.asks.range(..=price).map(|(_, level)| level.size).sum(),
+        }
+    }
+
+    /// First `n` levels of each side
+    pub fn top(&self, n: usize) -> DepthSnapshot {
+        DepthSnapshot {
+            bids: self.bids().take(n).collect(),
+            asks: self.asks().take(n).collect(),
+        }
+    }
+}
+
+/// Basis points in one
+const BPS: Fixed = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+/// `(price, size)` levels of both sides of a book from the best price, see [`OrderBook::top`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepthSnapshot {
+    pub bids: Vec<(Fixed, Fixed)>,
+    pub asks: Vec<(Fixed, Fixed)>,
+}
+
+#[cfg(test)]
+mod tests_order_book {
+    use rust_decimal::Decimal;
+
+    use super::OrderBook;
+    use crate::api::v5::Side;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn order_book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+        let mut book = OrderBook::default();
+        for (price, size) in bids {
+            book.handle_level(dec(price), dec(size), Side::Buy, false);
+        }
+        for (price, size) in asks {
+            book.handle_level(dec(price), dec(size), Side::Sell, false);
+        }
+        book
+    }
+
+    #[test]
+    fn test_queries() {
+        let book = order_book(
+            &[("99.5", "3"), ("99", "2"), ("100", "1")],
+            &[("100.5", "3"), ("101", "4")],
+        );
+        assert_eq!(book.best_bid(), Some((dec("100"), dec("1"))));
+        assert_eq!(book.best_ask(), Some((dec("100.5"), dec("3"))));
+        assert_eq!(book.mid(), Some(dec("100.25")));
+        assert_eq!(book.spread(), Some(dec("0.5")));
+        assert_eq!(book.spread_ticks(dec("0.1")), Some(dec("5")));
+        assert_eq!(book.spread_bps().unwrap().round_dp(4), dec("49.8753"));
+        // (100 * 3 + 100.5 * 1) / 4
+        assert_eq!(book.microprice(), Some(dec("100.125")));
+        assert!(!book.crossed());
+
+        assert_eq!(
+            book.bids().map(|(price, _)| price).collect::<Vec<_>>(),
+            [dec("100"), dec("99.5"), dec("99")]
+        );
+        assert_eq!(book.asks().next_back(), Some((dec("101"), dec("4"))));
+        assert_eq!(book.depth(Side::Buy), 3);
+        assert_eq!(book.size_to_price(Side::Buy, dec("99.5")), dec("4"));
+        assert_eq!(book.size_to_price(Side::Sell, dec("100.9")), dec("3"));
+        assert_eq!(book.size_to_price(Side::Sell, dec("100")), Decimal::ZERO);
+
+        let top = book.top(2);
+        assert_eq!(top.bids, [(dec("100"), dec("1")), (dec("99.5"), dec("3"))]);
+        assert_eq!(top.asks.len(), 2);
+
+        let one_sided = order_book(&[("100", "1")], &[]);
+        assert_eq!(one_sided.mid(), None);
+        assert_eq!(one_sided.top(5).bids.len(), 1);
+    }
 }
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::api::v5::ws_convert::ChannelResponse;
+use crate::book::book_manager::{BookManager, BookUpdateOutcome, BookUpdateType, ChecksumMismatch};
+use crate::websocket::conn::{BboTbt, Books};
+use crate::websocket::WebsocketChannel;
+
+/// Books of many instruments, keyed by channel and `instId`, fed with the output of
+/// [`Books::try_parse_books`]. A book is created on the first message of its channel and instrument.
+///
+/// ```
+/// use okx_rs::book::registry::BookRegistry;
+/// use okx_rs::websocket::conn::Books;
+/// use okx_rs::websocket::WebsocketChannel;
+///
+/// let mut registry = BookRegistry::default();
+/// let mut msg = format!(
+///     r#"{{"arg":{{"channel":"{}","instId":"BTC-USDT"}},"action":"snapshot","data":[{{"asks":[["101","1","0","1"]],"bids":[["99","2","0","1"]],"ts":"1","prevSeqId":-1,"seqId":10}}]}}"#,
+///     Books::CHANNEL
+/// );
+/// # #[cfg(not(feature = "simd"))]
+/// let response = Books::try_parse_books(&msg).unwrap().unwrap();
+/// # #[cfg(feature = "simd")]
+/// # let response = Books::try_parse_books(&mut msg).unwrap().unwrap();
+/// let routed = registry.handle(response).unwrap().unwrap();
+/// assert_eq!(routed.inst_id, "BTC-USDT");
+/// assert!(registry.by_instrument("BTC-USDT").next().is_some());
+/// ```
+#[derive(Debug, Default)]
+pub struct BookRegistry {
+    /// channel -> instId -> book
+    books: HashMap<String, HashMap<String, BookManager>>,
+}
+
+/// Book an update was routed to, borrowed from the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutedUpdate<'a> {
+    pub channel: &'a str,
+    pub inst_id: &'a str,
+    pub outcome: BookUpdateOutcome,
+}
+
+/// [`ChecksumMismatch`] of the book an update was routed to.
+/// The book drops updates until [`BookManager::resync`] or a new snapshot, see [`BookRegistry::get_mut`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{channel} {inst_id}: {mismatch}")]
+pub struct RoutedChecksumMismatch<'a> {
+    pub channel: &'a str,
+    pub inst_id: &'a str,
+    pub mismatch: ChecksumMismatch,
+}
+
+impl BookRegistry {
+    /// Routes a `books` / `bbo-tbt` push to the book of its channel and instrument.
+    /// Snapshots and updates are told apart by `action`, `bbo-tbt` pushes only update the top of book.
+    /// Returns `Ok(None)` for frames without data, e.g. subscription acks.
+    pub fn handle<'a>(
+        &mut self,
+        response: ChannelResponse<'a, Books>,
+    ) -> Result<Option<RoutedUpdate<'a>>, RoutedChecksumMismatch<'a>> {
+        let (Some(arg), Some([update])) = (response.arg, response.data) else {
+            return Ok(None);
+        };
+        let (Some(channel), Some(inst_id)) = (arg.channel, arg.inst_id) else {
+            return Ok(None);
+        };
+        let update_type = if BboTbt::matches_channel(channel) {
+            BookUpdateType::BBO
+        } else {
+            match response.action {
+                Some("update") => BookUpdateType::Diff,
+                // pushes without `action`, e.g. `books5`, carry the full depth
+                _ => BookUpdateType::Snapshot,
+            }
+        };
+        let outcome = self
+            .entry(channel, inst_id)
+            .handle_book_update(update, update_type)
+            .map_err(|mismatch| RoutedChecksumMismatch {
+                channel,
+                inst_id,
+                mismatch,
+            })?;
+        Ok(Some(RoutedUpdate {
+            channel,
+            inst_id,
+            outcome,
+        }))
+    }
+
+    pub fn get(&self, channel: &str, inst_id: &str) -> Option<&BookManager> {
+        self.books.get(channel)?.get(inst_id)
+    }
+
+    pub fn get_mut(&mut self, channel: &str, inst_id: &str) -> Option<&mut BookManager> {
+        self.books.get_mut(channel)?.get_mut(inst_id)
+    }
+
+    /// Books of `inst_id` on every channel, with their channel
+    pub fn by_instrument<'a>(
+        &'a self,
+        inst_id: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a BookManager)> + 'a {
+        self.books
+            .iter()
+            .filter_map(move |(channel, books)| Some((channel.as_str(), books.get(inst_id)?)))
+    }
+
+    /// Every book with its channel and `instId`
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &BookManager)> {
+        self.books.iter().flat_map(|(channel, books)| {
+            books
+                .iter()
+                .map(move |(inst_id, book)| (channel.as_str(), inst_id.as_str(), book))
+        })
+    }
+
+    /// Tracks `book` for `channel` and `inst_id`, e.g. restored from a [`snapshot`](crate::book::snapshot).
+    /// Returns the book it replaces
+    pub fn insert(
+        &mut self,
+        channel: impl Into<String>,
+        inst_id: impl Into<String>,
+        book: BookManager,
+    ) -> Option<BookManager> {
+        self.books
+            .entry(channel.into())
+            .or_default()
+            .insert(inst_id.into(), book)
+    }
+
+    /// Stops tracking a book, e.g. after unsubscribing
+    pub fn remove(&mut self, channel: &str, inst_id: &str) -> Option<BookManager> {
+        self.books.get_mut(channel)?.remove(inst_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.values().all(HashMap::is_empty)
+    }
+
+    /// Book of `channel` and `inst_id`, keys are only allocated for new books
+    fn entry(&mut self, channel: &str, inst_id: &str) -> &mut BookManager {
+        if !self.books.contains_key(channel) {
+            self.books.insert(channel.to_string(), HashMap::new());
+        }
+        let books = self.books.get_mut(channel).unwrap();
+        if !books.contains_key(inst_id) {
+            books.insert(inst_id.to_string(), BookManager::default());
+        }
+        books.get_mut(inst_id).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests_book_registry {
+    use super::BookRegistry;
+    use crate::api::v5::ws_convert::ChannelResponse;
+    use crate::book::book_manager::BookUpdateOutcome;
+    use crate::websocket::conn::Books;
+    use crate::websocket::WebsocketChannel;
+
+    fn parse(msg: &mut str) -> ChannelResponse<'_, Books> {
+        Books::try_parse_books(msg).unwrap().unwrap()
+    }
+
+    fn push(channel: &str, inst_id: &str, action: &str, prev_seq_id: i64, seq_id: i64) -> String {
+        format!(
+            r#"{{"arg":{{"channel":"{channel}","instId":"{inst_id}"}},"action":"{action}","data":[{{"asks":[["101","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"1","prevSeqId":{prev_seq_id},"seqId":{seq_id}}}]}}"#
+        )
+    }
+
+    #[test]
+    fn test_route_by_instrument() {
+        let mut registry = BookRegistry::default();
+        for inst_id in ["BTC-USDT", "ETH-USDT"] {
+            let mut msg = push(Books::CHANNEL, inst_id, "snapshot", -1, 10);
+            let routed = registry.handle(parse(&mut msg)).unwrap().unwrap();
+            assert_eq!(routed.inst_id, inst_id);
+            assert_eq!(routed.outcome, BookUpdateOutcome::Applied);
+        }
+        let mut msg = push(Books::CHANNEL, "BTC-USDT", "update", 10, 11);
+        let routed = registry.handle(parse(&mut msg)).unwrap().unwrap();
+        assert_eq!(routed.outcome, BookUpdateOutcome::Applied);
+        // updates are not applied to another instrument's book
+        let mut msg = push(Books::CHANNEL, "ETH-USDT", "update", 11, 12);
+        let routed = registry.handle(parse(&mut msg)).unwrap().unwrap();
+        assert!(matches!(
+            routed.outcome,
+            BookUpdateOutcome::GapDetected { .. }
+        ));
+
+        let mut msg = r#"{"arg":{"channel":"bbo-tbt","instId":"BTC-USDT"},"data":[{"asks":[["100.5","1","0","1"]],"bids":[["100","1","0","1"]],"ts":"2","seqId":3}]}"#.to_string();
+        let routed = registry.handle(parse(&mut msg)).unwrap().unwrap();
+        assert_eq!(routed.channel, "bbo-tbt");
+
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.by_instrument("BTC-USDT").count(), 2);
+        assert_eq!(
+            registry.get(Books::CHANNEL, "BTC-USDT").unwrap().last_seq,
+            Some(11)
+        );
+        assert!(registry.remove(Books::CHANNEL, "ETH-USDT").is_some());
+        assert_eq!(registry.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_checksum_mismatch_names_book() {
+        let snapshot = |inst_id: &str| {
+            format!(
+                r#"{{"arg":{{"channel":"{}","instId":"{inst_id}"}},"action":"snapshot","data":[{{"asks":[["101","1","0","1"]],"bids":[["99","1","0","1"]],"ts":"1","prevSeqId":-1,"seqId":10}}]}}"#,
+                Books::CHANNEL
+            )
+        };
+        let mut registry = BookRegistry::default();
+        for inst_id in ["BTC-USDT", "ETH-USDT"] {
+            let mut msg = snapshot(inst_id);
+            registry.handle(parse(&mut msg)).unwrap();
+        }
+
+        let mut corrupt = format!(
+            r#"{{"arg":{{"channel":"{}","instId":"ETH-USDT"}},"action":"update","data":[{{"asks":[["102","1","0","1"]],"bids":[],"ts":"2","checksum":1,"prevSeqId":10,"seqId":11}}]}}"#,
+            Books::CHANNEL
+        );
+        let err = registry.handle(parse(&mut corrupt)).unwrap_err();
+        assert_eq!(err.channel, Books::CHANNEL);
+        assert_eq!(err.inst_id, "ETH-USDT");
+        assert_eq!(err.mismatch.seq_id, Some(11));
+
+        let (channel, inst_id) = (err.channel.to_string(), err.inst_id.to_string());
+        assert!(registry.get(&channel, &inst_id).unwrap().needs_resync());
+        assert!(!registry
+            .get(Books::CHANNEL, "BTC-USDT")
+            .unwrap()
+            .needs_resync());
+        let mut msg = push(Books::CHANNEL, "BTC-USDT", "update", 10, 11);
+        let routed = registry.handle(parse(&mut msg)).unwrap().unwrap();
+        assert_eq!(routed.outcome, BookUpdateOutcome::Applied);
+    }
+}
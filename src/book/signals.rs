@@ -0,0 +1,529 @@
+//! Features derived from an [`OrderBook`]: size and order count imbalances, liquidity within bands of
+//! mid and book slope.
+//!
+//! [`Signals`] keeps running sums of the levels each feature reads and adjusts them with the
+//! [`LevelEvent`]s of each update. Levels are only summed again when a level is inserted or removed
+//! within the first levels of a side, and when mid moves only the levels a band edge crossed are.
+//!
+//! ```
+//! use okx_rs::book::signals::{SignalConfig, Signals};
+//! # use okx_rs::book::book_manager::BookManager;
+//! # let manager = BookManager::default();
+//!
+//! let mut signals = Signals::new(SignalConfig::default());
+//! // after each update handled by `manager`
+//! let features = signals.update(&manager);
+//! assert_eq!(features.imbalance, None);
+//! ```
+
+use rust_decimal::Decimal;
+
+use crate::api::v5::Side;
+use std::cmp::Reverse;
+use std::ops::Bound;
+
+use crate::book::book_manager::BookManager;
+use crate::book::events::{LevelChange, LevelEvent};
+use crate::book::{Fixed, OrderBook, BPS};
+
+impl OrderBook {
+    /// `(bid - ask) / (bid + ask)` of the size of the first `depth` levels of each side, from -1 to 1
+    pub fn imbalance(&self, depth: usize) -> Option<Fixed> {
+        let bid: Fixed = self.bids().take(depth).map(|(_, size)| size).sum();
+        let ask: Fixed = self.asks().take(depth).map(|(_, size)| size).sum();
+        (bid - ask).checked_div(bid + ask)
+    }
+
+    /// [`OrderBook::imbalance`] of the number of orders, `None` when no level has it
+    pub fn order_imbalance(&self, depth: usize) -> Option<Fixed> {
+        let bid: u64 = self
+            .bids
+            .values()
+            .take(depth)
+            .map(|level| level.orders as u64)
+            .sum();
+        let ask: u64 = self
+            .asks
+            .values()
+            .take(depth)
+            .map(|level| level.orders as u64)
+            .sum();
+        (Decimal::from(bid) - Decimal::from(ask)).checked_div(Decimal::from(bid + ask))
+    }
+
+    /// Size of the first `depth` levels of `side` per basis point of mid to the deepest of them,
+    /// higher for a denser side. `None` without mid
+    pub fn slope(&self, side: Side, depth: usize) -> Option<Fixed> {
+        let mid = self.mid()?;
+        let (size, deepest) = match side {
+            Side::Buy => cumulative(self.bids().take(depth)),
+            Side::Sell => cumulative(self.asks().take(depth)),
+        };
+        let distance_bps = ((deepest? - mid).abs() * BPS).checked_div(mid)?;
+        size.checked_div(distance_bps)
+    }
+}
+
+/// Total size and last price of `levels`
+fn cumulative(levels: impl Iterator<Item = (Fixed, Fixed)>) -> (Fixed, Option<Fixed>) {
+    levels.fold((Decimal::ZERO, None), |(total, _), (price, size)| {
+        (total + size, Some(price))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalConfig {
+    /// Levels of each side in [`BookSignals::imbalance`] and [`BookSignals::order_imbalance`]
+    pub depth: usize,
+    /// Bands of [`BookSignals::liquidity`], in basis points of mid
+    pub bands_bps: Vec<Fixed>,
+    /// Levels of each side in [`BookSignals::bid_slope`] and [`BookSignals::ask_slope`]
+    pub slope_depth: usize,
+}
+
+impl Default for SignalConfig {
+    fn default() -> Self {
+        Self {
+            depth: 5,
+            bands_bps: vec![Decimal::from(10), Decimal::from(25), Decimal::from(50)],
+            slope_depth: 10,
+        }
+    }
+}
+
+/// Size resting within `bps` basis points of mid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandLiquidity {
+    pub bps: Fixed,
+    pub bid: Fixed,
+    pub ask: Fixed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookSignals {
+    /// See [`OrderBook::imbalance`]
+    pub imbalance: Option<Fixed>,
+    /// See [`OrderBook::order_imbalance`]
+    pub order_imbalance: Option<Fixed>,
+    /// Liquidity of each band of [`SignalConfig::bands_bps`], empty without mid
+    pub liquidity: Vec<BandLiquidity>,
+    /// See [`OrderBook::slope`]
+    pub bid_slope: Option<Fixed>,
+    pub ask_slope: Option<Fixed>,
+}
+
+/// Running size and number of orders of the first levels of a side
+#[derive(Debug, Clone, Copy, Default)]
+struct TopLevels {
+    size: Fixed,
+    orders: i64,
+    /// Price of the deepest level summed
+    deepest: Option<Fixed>,
+    /// Whether the side holds as many levels as summed, otherwise all of them are
+    full: bool,
+    /// A level was inserted or removed among the levels summed, they are summed again
+    dirty: bool,
+}
+
+impl TopLevels {
+    fn sum(levels: impl Iterator<Item = (Fixed, Fixed, u32)>, depth: usize) -> Self {
+        let mut top = Self::default();
+        let mut count = 0;
+        for (price, size, orders) in levels.take(depth) {
+            top.size += size;
+            top.orders += orders as i64;
+            top.deepest = Some(price);
+            count += 1;
+        }
+        top.full = count == depth;
+        top
+    }
+
+    fn contains(&self, side: Side, price: Fixed) -> bool {
+        match (self.full, self.deepest, side) {
+            (false, _, _) => true,
+            (true, None, _) => false,
+            (true, Some(deepest), Side::Buy) => price >= deepest,
+            (true, Some(deepest), Side::Sell) => price <= deepest,
+        }
+    }
+
+    fn apply(&mut self, event: &LevelEvent) {
+        if self.dirty || !self.contains(event.side, event.price) {
+            return;
+        }
+        match event.change {
+            LevelChange::Update => {
+                self.size += event.size - event.prev_size;
+                self.orders += event.orders as i64 - event.prev_orders as i64;
+            }
+            // another level becomes the deepest one summed
+            LevelChange::Insert | LevelChange::Remove => self.dirty = true,
+        }
+    }
+}
+
+/// [`TopLevels`] of both sides
+#[derive(Debug, Clone, Copy, Default)]
+struct Top {
+    depth: usize,
+    bid: TopLevels,
+    ask: TopLevels,
+}
+
+impl Top {
+    fn new(book: &OrderBook, depth: usize) -> Self {
+        Self {
+            depth,
+            bid: TopLevels::sum(book.bids_with_orders(), depth),
+            ask: TopLevels::sum(book.asks_with_orders(), depth),
+        }
+    }
+
+    fn apply(&mut self, event: &LevelEvent) {
+        match event.side {
+            Side::Buy => self.bid.apply(event),
+            Side::Sell => self.ask.apply(event),
+        }
+    }
+
+    /// Sums the levels of the sides marked dirty again
+    fn refresh(&mut self, book: &OrderBook) {
+        if self.bid.dirty {
+            self.bid = TopLevels::sum(book.bids_with_orders(), self.depth);
+        }
+        if self.ask.dirty {
+            self.ask = TopLevels::sum(book.asks_with_orders(), self.depth);
+        }
+    }
+
+    fn imbalance(&self) -> Option<Fixed> {
+        (self.bid.size - self.ask.size).checked_div(self.bid.size + self.ask.size)
+    }
+
+    fn order_imbalance(&self) -> Option<Fixed> {
+        let (bid, ask) = (
+            Decimal::from(self.bid.orders),
+            Decimal::from(self.ask.orders),
+        );
+        (bid - ask).checked_div(bid + ask)
+    }
+}
+
+/// See [`OrderBook::slope`]
+fn slope(top: &TopLevels, mid: Option<Fixed>) -> Option<Fixed> {
+    let mid = mid?;
+    let distance_bps = ((top.deepest? - mid).abs() * BPS).checked_div(mid)?;
+    top.size.checked_div(distance_bps)
+}
+
+/// Running size within a band of mid, bids at or above `bid_edge` and asks at or below `ask_edge`
+#[derive(Debug, Clone, Copy)]
+struct Band {
+    bps: Fixed,
+    bid_edge: Fixed,
+    ask_edge: Fixed,
+    bid: Fixed,
+    ask: Fixed,
+}
+
+impl Band {
+    fn edges(mid: Fixed, bps: Fixed) -> (Fixed, Fixed) {
+        let offset = mid * bps / BPS;
+        (mid - offset, mid + offset)
+    }
+
+    fn new(book: &OrderBook, mid: Fixed, bps: Fixed) -> Self {
+        let (bid_edge, ask_edge) = Self::edges(mid, bps);
+        Self {
+            bps,
+            bid_edge,
+            ask_edge,
+            bid: book.size_to_price(Side::Buy, bid_edge),
+            ask: book.size_to_price(Side::Sell, ask_edge),
+        }
+    }
+
+    fn apply(&mut self, event: &LevelEvent) {
+        let delta = event.size - event.prev_size;
+        match event.side {
+            Side::Buy if event.price >= self.bid_edge => self.bid += delta,
+            Side::Sell if event.price <= self.ask_edge => self.ask += delta,
+            _ => {}
+        }
+    }
+
+    /// Moves the edges to `mid`, adding or removing the levels they crossed
+    fn recenter(&mut self, book: &OrderBook, mid: Fixed) {
+        let (bid_edge, ask_edge) = Self::edges(mid, self.bps);
+        if bid_edge < self.bid_edge {
+            self.bid += bid_size_between(book, bid_edge, self.bid_edge);
+        } else if bid_edge > self.bid_edge {
+            self.bid -= bid_size_between(book, self.bid_edge, bid_edge);
+        }
+        if ask_edge > self.ask_edge {
+            self.ask += ask_size_between(book, self.ask_edge, ask_edge);
+        } else if ask_edge < self.ask_edge {
+            self.ask -= ask_size_between(book, ask_edge, self.ask_edge);
+        }
+        self.bid_edge = bid_edge;
+        self.ask_edge = ask_edge;
+    }
+}
+
+/// Size of the bids priced from `low` up to but excluding `high`
+fn bid_size_between(book: &OrderBook, low: Fixed, high: Fixed) -> Fixed {
+    book.bids
+        .range((
+            Bound::Excluded(Reverse(high)),
+            Bound::Included(Reverse(low)),
+        ))
+        .map(|(_, level)| level.size)
+        .sum()
+}
+
+/// Size of the asks priced above `low` up to `high`
+fn ask_size_between(book: &OrderBook, low: Fixed, high: Fixed) -> Fixed {
+    book.asks
+        .range((Bound::Excluded(low), Bound::Included(high)))
+        .map(|(_, level)| level.size)
+        .sum()
+}
+
+/// [`BookSignals`] of a book, updated after each update it handles
+#[derive(Debug, Clone)]
+pub struct Signals {
+    config: SignalConfig,
+    signals: BookSignals,
+    /// Levels of the imbalances
+    top: Top,
+    /// Levels of the slopes
+    slope: Top,
+    /// Bands of [`SignalConfig::bands_bps`] around `mid`, empty without mid
+    bands: Vec<Band>,
+    mid: Option<Fixed>,
+    initialized: bool,
+}
+
+impl Signals {
+    pub fn new(config: SignalConfig) -> Self {
+        Self {
+            config,
+            signals: BookSignals::default(),
+            top: Top::default(),
+            slope: Top::default(),
+            bands: Vec::new(),
+            mid: None,
+            initialized: false,
+        }
+    }
+
+    #[inline]
+    pub fn signals(&self) -> &BookSignals {
+        &self.signals
+    }
+
+    /// Updates the signals with the last update handled by `manager`, see [`BookManager::changes`].
+    /// Has to be called after every update so that no change is missed.
+    pub fn update(&mut self, manager: &BookManager) -> &BookSignals {
+        let (book, changes) = (manager.book(), manager.changes());
+        if !self.initialized || changes.reset {
+            self.top = Top::new(book, self.config.depth);
+            self.slope = Top::new(book, self.config.slope_depth);
+            self.mid = book.mid();
+            self.bands = self.new_bands(book);
+            self.initialized = true;
+        } else {
+            for event in &changes.levels {
+                self.top.apply(event);
+                self.slope.apply(event);
+                for band in &mut self.bands {
+                    band.apply(event);
+                }
+            }
+            self.top.refresh(book);
+            self.slope.refresh(book);
+
+            let mid = book.mid();
+            if mid != self.mid {
+                self.mid = mid;
+                match mid {
+                    Some(mid) if !self.bands.is_empty() => {
+                        for band in &mut self.bands {
+                            band.recenter(book, mid);
+                        }
+                    }
+                    _ => self.bands = self.new_bands(book),
+                }
+            }
+        }
+        self.publish();
+        &self.signals
+    }
+
+    fn new_bands(&self, book: &OrderBook) -> Vec<Band> {
+        match self.mid {
+            Some(mid) => self
+                .config
+                .bands_bps
+                .iter()
+                .map(|&bps| Band::new(book, mid, bps))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn publish(&mut self) {
+        self.signals.imbalance = self.top.imbalance();
+        self.signals.order_imbalance = self.top.order_imbalance();
+        self.signals.bid_slope = slope(&self.slope.bid, self.mid);
+        self.signals.ask_slope = slope(&self.slope.ask, self.mid);
+        self.signals.liquidity.clear();
+        self.signals
+            .liquidity
+            .extend(self.bands.iter().map(|band| BandLiquidity {
+                bps: band.bps,
+                bid: band.bid,
+                ask: band.ask,
+            }));
+    }
+}
+
+#[cfg(test)]
+mod tests_signals {
+    use rust_decimal::Decimal;
+
+    use super::{SignalConfig, Signals};
+    use crate::api::v5::{BookUpdate, Side};
+    use crate::book::book_manager::{BookManager, BookUpdateType};
+    use crate::book::OrderBook;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn handle(manager: &mut BookManager, update: &str, update_type: BookUpdateType) {
+        let update = serde_json::from_str::<BookUpdate>(update).unwrap();
+        manager.handle_book_update(update, update_type).unwrap();
+    }
+
+    #[test]
+    fn test_book_signals() {
+        let mut book = OrderBook::default();
+        for (price, size, orders) in [("100", "3", 2), ("99", "1", 1), ("98", "4", 1)] {
+            book.handle_level_with_orders(dec(price), dec(size), orders, Side::Buy, false);
+        }
+        for (price, size, orders) in [("101", "1", 1), ("102", "1", 3)] {
+            book.handle_level_with_orders(dec(price), dec(size), orders, Side::Sell, false);
+        }
+        // (4 - 2) / 6
+        assert_eq!(book.imbalance(2).unwrap().round_dp(4), dec("0.3333"));
+        // (2 - 1) / 3
+        assert_eq!(book.order_imbalance(1).unwrap().round_dp(4), dec("0.3333"));
+        assert_eq!(book.order_imbalance(5), Some(Decimal::ZERO));
+        // 8 over 248.76 bps, 2.5 from the mid of 100.5
+        assert_eq!(book.slope(Side::Buy, 3).unwrap().round_dp(4), dec("0.0322"));
+        assert_eq!(
+            book.slope(Side::Sell, 1).unwrap().round_dp(4),
+            dec("0.0201")
+        );
+        assert_eq!(OrderBook::default().slope(Side::Buy, 3), None);
+    }
+
+    #[test]
+    fn test_incremental_signals() {
+        let config = SignalConfig {
+            depth: 2,
+            bands_bps: vec![dec("100")],
+            slope_depth: 2,
+        };
+        let mut manager = BookManager::default();
+        let mut signals = Signals::new(config.clone());
+        let updates = [
+            (
+                r#"{"asks":[["101","1","0","1"],["102","2","0","2"],["110","5","0","1"]],"bids":[["100","2","0","1"],["99","1","0","3"],["90","5","0","1"]],"ts":"1","prevSeqId":-1,"seqId":1}"#,
+                BookUpdateType::Snapshot,
+            ),
+            // deeper than the levels read and out of the bands
+            (
+                r#"{"asks":[["110","7","0","2"]],"bids":[],"ts":"2","prevSeqId":1,"seqId":2}"#,
+                BookUpdateType::Diff,
+            ),
+            // second level
+            (
+                r#"{"asks":[],"bids":[["99","4","0","1"]],"ts":"3","prevSeqId":2,"seqId":3}"#,
+                BookUpdateType::Diff,
+            ),
+            // new second level, within the band
+            (
+                r#"{"asks":[],"bids":[["99.8","0","0","0"],["99.5","3","0","1"]],"ts":"4","prevSeqId":3,"seqId":4}"#,
+                BookUpdateType::Diff,
+            ),
+            // top of book
+            (
+                r#"{"asks":[["101","0","0","0"]],"bids":[],"ts":"5","prevSeqId":4,"seqId":5}"#,
+                BookUpdateType::Diff,
+            ),
+        ];
+        for (update, update_type) in updates {
+            handle(&mut manager, update, update_type);
+            let incremental = signals.update(&manager).clone();
+            let full = Signals::new(config.clone()).update(&manager).clone();
+            assert_eq!(incremental, full, "{update}");
+        }
+        let features = signals.signals();
+        // 100 @ 2 and 102 @ 2 within 1% of 101
+        assert_eq!(features.liquidity[0].bid, dec("2"));
+        assert_eq!(features.liquidity[0].ask, dec("2"));
+        // (5 - 9) / 14
+        assert_eq!(features.imbalance.unwrap().round_dp(4), dec("-0.2857"));
+    }
+
+    #[test]
+    fn test_incremental_signals_random_updates() {
+        let config = SignalConfig {
+            depth: 3,
+            bands_bps: vec![dec("10"), dec("50"), dec("100")],
+            slope_depth: 5,
+        };
+        let mut manager = BookManager::default();
+        let mut signals = Signals::new(config.clone());
+        handle(
+            &mut manager,
+            r#"{"asks":[["100.1","1","0","1"],["100.5","2","0","2"]],"bids":[["100","1","0","1"],["99.5","2","0","1"]],"ts":"1","prevSeqId":-1,"seqId":1}"#,
+            BookUpdateType::Snapshot,
+        );
+        signals.update(&manager);
+
+        // bids from 99 to 100 and asks from 100.1 to 101.1 by 0.1, so that the book never crosses
+        let mut seed = 42u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % bound
+        };
+        for seq_id in 2..500i64 {
+            let mut levels = |base: u64| {
+                (0..1 + next(3))
+                    .map(|_| {
+                        let price = Decimal::new((base + next(11)) as i64, 1);
+                        // a third of the levels are removed
+                        let size = next(3);
+                        format!(r#"["{price}","{size}","0","{}"]"#, next(4))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            let (bids, asks) = (levels(990), levels(1001));
+            let update = format!(
+                r#"{{"asks":[{asks}],"bids":[{bids}],"ts":"{seq_id}","prevSeqId":{},"seqId":{seq_id}}}"#,
+                seq_id - 1
+            );
+            handle(&mut manager, &update, BookUpdateType::Diff);
+            let incremental = signals.update(&manager).clone();
+            let full = Signals::new(config.clone()).update(&manager).clone();
+            assert_eq!(incremental, full, "{update}");
+        }
+    }
+}
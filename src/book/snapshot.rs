@@ -0,0 +1,367 @@
+//! Snapshots of books, to restore them on a warm restart, persist them for research
+//! or validate them against the REST order book.
+//!
+//! [`BookSnapshot`] serializes to JSON with prices and sizes as strings, e.g.
+//! `{"lastSeq":10,"lastExchTs":1,"bids":[["99","2",1]],"asks":[["101","1",3]]}`
+//! with the number of orders of each level last.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::v5::orderbook_trading::market_data::OrderBookSnapshot;
+use crate::api::v5::Side;
+use crate::book::book_manager::BookManager;
+use crate::book::registry::BookRegistry;
+use crate::book::{Fixed, OrderBook};
+
+/// Levels of a book from the best price with the sequence of the last update applied
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookSnapshot {
+    pub last_seq: Option<i64>,
+    pub last_exch_ts: Option<u64>,
+    /// `(price, size, orders)` levels, orders are zero when unknown
+    pub bids: Vec<(Fixed, Fixed, u32)>,
+    pub asks: Vec<(Fixed, Fixed, u32)>,
+}
+
+/// Snapshot file of a book, see [`SnapshotWriter`]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredBook {
+    channel: String,
+    inst_id: String,
+    #[serde(flatten)]
+    snapshot: BookSnapshot,
+}
+
+impl OrderBook {
+    /// Book of `(price, size)` levels, levels without a positive size are skipped
+    pub fn from_levels(
+        bids: impl IntoIterator<Item = (Fixed, Fixed)>,
+        asks: impl IntoIterator<Item = (Fixed, Fixed)>,
+    ) -> Self {
+        let mut book = Self::default();
+        for (price, size) in bids {
+            book.handle_level(price, size, Side::Buy, false);
+        }
+        for (price, size) in asks {
+            book.handle_level(price, size, Side::Sell, false);
+        }
+        book
+    }
+
+    /// [`OrderBook::from_levels`] of `(price, size, orders)` levels, e.g. of a [`BookSnapshot`]
+    pub fn from_levels_with_orders(
+        bids: impl IntoIterator<Item = (Fixed, Fixed, u32)>,
+        asks: impl IntoIterator<Item = (Fixed, Fixed, u32)>,
+    ) -> Self {
+        let mut book = Self::default();
+        for (price, size, orders) in bids {
+            book.handle_level_with_orders(price, size, orders, Side::Buy, false);
+        }
+        for (price, size, orders) in asks {
+            book.handle_level_with_orders(price, size, orders, Side::Sell, false);
+        }
+        book
+    }
+
+    /// Levels of this book differing from `bids` and `asks`, given from the best price, e.g. the
+    /// top of the book from the REST API. Levels deeper than the last one given on a side are not compared.
+    pub fn diff(
+        &self,
+        bids: impl IntoIterator<Item = (Fixed, Fixed)>,
+        asks: impl IntoIterator<Item = (Fixed, Fixed)>,
+    ) -> Vec<LevelDiff> {
+        let mut diffs = Vec::new();
+        diff_side(Side::Buy, self.bids(), bids, &mut diffs);
+        diff_side(Side::Sell, self.asks(), asks, &mut diffs);
+        diffs
+    }
+
+    /// [`OrderBook::diff`] against the response of
+    /// [`GetOrderBook`](crate::api::v5::orderbook_trading::market_data::GetOrderBook),
+    /// levels that are not decimal numbers are skipped
+    pub fn diff_rest(&self, snapshot: &OrderBookSnapshot) -> Vec<LevelDiff> {
+        self.diff(rest_levels(&snapshot.bids), rest_levels(&snapshot.asks))
+    }
+}
+
+/// Level with a different size in two books, `None` where a book does not have the level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelDiff {
+    pub side: Side,
+    pub price: Fixed,
+    pub ours: Option<Fixed>,
+    pub theirs: Option<Fixed>,
+}
+
+fn rest_levels(levels: &[[String; 4]]) -> impl Iterator<Item = (Fixed, Fixed)> + '_ {
+    levels
+        .iter()
+        .filter_map(|[price, size, ..]| Some((price.parse().ok()?, size.parse().ok()?)))
+}
+
+/// Merges two sides sorted from the best price
+fn diff_side(
+    side: Side,
+    ours: impl Iterator<Item = (Fixed, Fixed)>,
+    theirs: impl IntoIterator<Item = (Fixed, Fixed)>,
+    diffs: &mut Vec<LevelDiff>,
+) {
+    // `Less` when `a` is a better price than `b`
+    let better = |a: &Fixed, b: &Fixed| match side {
+        Side::Buy => b.cmp(a),
+        Side::Sell => a.cmp(b),
+    };
+    let theirs = theirs.into_iter().collect::<Vec<_>>();
+    let deepest = theirs.last().map(|(price, _)| *price);
+    let mut ours = ours
+        .take_while(|(price, _)| deepest.is_none_or(|deepest| better(price, &deepest).is_le()))
+        .peekable();
+    let mut theirs = theirs.into_iter().peekable();
+    loop {
+        let (ours_level, theirs_level) = (ours.peek().copied(), theirs.peek().copied());
+        let order = match (ours_level, theirs_level) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((ours_price, _)), Some((theirs_price, _))) => better(&ours_price, &theirs_price),
+        };
+        if order.is_le() {
+            ours.next();
+        }
+        if order.is_ge() {
+            theirs.next();
+        }
+        let diff = match (order, ours_level, theirs_level) {
+            (Ordering::Less, Some((price, size)), _) => (price, Some(size), None),
+            (Ordering::Greater, _, Some((price, size))) => (price, None, Some(size)),
+            (Ordering::Equal, Some((price, ours)), Some((_, theirs))) if ours != theirs => {
+                (price, Some(ours), Some(theirs))
+            }
+            _ => continue,
+        };
+        diffs.push(LevelDiff {
+            side,
+            price: diff.0,
+            ours: diff.1,
+            theirs: diff.2,
+        });
+    }
+}
+
+/// Writes the books of a [`BookRegistry`] to a directory at most once per interval,
+/// one `{channel}@{instId}.json` file per book. Books out of sync are not written.
+///
+/// Files are written to a temporary file first and renamed, so that a crash never leaves a
+/// partial snapshot behind. [`load`] restores a registry from the directory.
+#[derive(Debug)]
+pub struct SnapshotWriter {
+    dir: PathBuf,
+    interval: Duration,
+    last_write: Option<Instant>,
+}
+
+impl SnapshotWriter {
+    /// Creates `dir` if it does not exist
+    pub fn new(dir: impl Into<PathBuf>, interval: Duration) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            interval,
+            last_write: None,
+        })
+    }
+
+    /// Writes the books of `registry` if `interval` elapsed since the last write,
+    /// returns the number of books written
+    pub fn poll(&mut self, registry: &BookRegistry) -> io::Result<usize> {
+        match self.last_write {
+            Some(last_write) if last_write.elapsed() < self.interval => Ok(0),
+            _ => self.write_all(registry),
+        }
+    }
+
+    /// Writes the books of `registry` now, returns the number of books written
+    pub fn write_all(&mut self, registry: &BookRegistry) -> io::Result<usize> {
+        self.last_write = Some(Instant::now());
+        let mut written = 0;
+        for (channel, inst_id, manager) in registry.iter() {
+            let synced = manager.last_seq.is_some() || manager.last_exch_ts.is_some();
+            if !synced || manager.needs_resync() {
+                continue;
+            }
+            let stored = StoredBook {
+                channel: channel.to_string(),
+                inst_id: inst_id.to_string(),
+                snapshot: manager.snapshot(),
+            };
+            let json = serde_json::to_vec(&stored)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let path = self.dir.join(format!("{channel}@{inst_id}.json"));
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, json)?;
+            fs::rename(&tmp, &path)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+/// Registry of the books written to `dir` by a [`SnapshotWriter`]
+pub fn load(dir: impl AsRef<Path>) -> io::Result<BookRegistry> {
+    let mut registry = BookRegistry::default();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let stored: StoredBook = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut manager = BookManager::default();
+        manager.restore(stored.snapshot);
+        registry.insert(stored.channel, stored.inst_id, manager);
+    }
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests_snapshot {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use rust_decimal::Decimal;
+
+    use super::{load, BookSnapshot, LevelDiff, SnapshotWriter};
+    use crate::api::v5::orderbook_trading::market_data::OrderBookSnapshot;
+    use crate::api::v5::{BookUpdate, Side};
+    use crate::book::book_manager::{BookManager, BookUpdateOutcome, BookUpdateType};
+    use crate::book::registry::BookRegistry;
+    use crate::book::OrderBook;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("okx-rs-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&path);
+        path
+    }
+
+    fn synced_manager() -> BookManager {
+        let snapshot = r#"{"asks":[["101","1","0","1"],["102","3","0","2"]],"bids":[["99.0","2","0","1"]],"ts":"5","prevSeqId":-1,"seqId":10}"#;
+        let mut manager = BookManager::default();
+        let update = serde_json::from_str::<BookUpdate>(snapshot).unwrap();
+        manager
+            .handle_book_update(update, BookUpdateType::Snapshot)
+            .unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let manager = synced_manager();
+        let snapshot = manager.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            json,
+            r#"{"lastSeq":10,"lastExchTs":5,"bids":[["99.0","2",1]],"asks":[["101","1",1],["102","3",2]]}"#
+        );
+        let restored = serde_json::from_str::<BookSnapshot>(&json).unwrap();
+        assert_eq!(restored, snapshot);
+
+        let mut manager = BookManager::default();
+        manager.restore(restored);
+        assert_eq!(
+            manager.book().checksum(),
+            synced_manager().book().checksum()
+        );
+        assert_eq!(
+            manager
+                .book()
+                .asks_with_orders()
+                .map(|(_, _, orders)| orders)
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(
+            manager.book().order_imbalance(5),
+            synced_manager().book().order_imbalance(5)
+        );
+        // updates continue from the restored sequence
+        let update =
+            r#"{"asks":[["101","0","0","0"]],"bids":[],"ts":"6","prevSeqId":10,"seqId":11}"#;
+        let update = serde_json::from_str::<BookUpdate>(update).unwrap();
+        assert_eq!(
+            manager.handle_book_update(update, BookUpdateType::Diff),
+            Ok(BookUpdateOutcome::Applied)
+        );
+        assert_eq!(manager.book().best_ask(), Some((dec("102"), dec("3"))));
+    }
+
+    #[test]
+    fn test_snapshot_writer() {
+        let dir = temp_dir("snapshots");
+        let mut registry = BookRegistry::default();
+        registry.insert("books", "BTC-USDT", synced_manager());
+        registry.insert("books", "ETH-USDT", BookManager::default());
+        let mut writer = SnapshotWriter::new(&dir, Duration::from_secs(60)).unwrap();
+        // books never synced are not written
+        assert_eq!(writer.poll(&registry).unwrap(), 1);
+        assert_eq!(writer.poll(&registry).unwrap(), 0);
+        assert!(dir.join("books@BTC-USDT.json").exists());
+
+        let loaded = load(&dir).unwrap();
+        assert_eq!(loaded.len(), 1);
+        let manager = loaded.get("books", "BTC-USDT").unwrap();
+        assert_eq!(manager.snapshot(), synced_manager().snapshot());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_diff() {
+        let book = OrderBook::from_levels(
+            [
+                (dec("100"), dec("1")),
+                (dec("99"), dec("2")),
+                (dec("97"), dec("1")),
+            ],
+            [(dec("101"), dec("1")), (dec("103"), dec("1"))],
+        );
+        let rest = r#"{"asks":[["101","1","0","1"],["102","4","0","1"]],"bids":[["100","1.0","0","1"],["99","3","0","2"],["98","1","0","1"]],"ts":"7"}"#;
+        let rest = serde_json::from_str::<OrderBookSnapshot>(rest).unwrap();
+        // 97 is deeper than the REST bids and 103 than the REST asks
+        assert_eq!(
+            book.diff_rest(&rest),
+            [
+                LevelDiff {
+                    side: Side::Buy,
+                    price: dec("99"),
+                    ours: Some(dec("2")),
+                    theirs: Some(dec("3")),
+                },
+                LevelDiff {
+                    side: Side::Buy,
+                    price: dec("98"),
+                    ours: None,
+                    theirs: Some(dec("1")),
+                },
+                LevelDiff {
+                    side: Side::Sell,
+                    price: dec("102"),
+                    ours: None,
+                    theirs: Some(dec("4")),
+                },
+            ]
+        );
+        assert!(book.diff(book.bids(), book.asks()).is_empty());
+    }
+}
@@ -1,5 +1,7 @@
-use serde::{de, Deserialize, Deserializer, Serializer};
-use std::fmt::Display;
+use serde::de::Visitor;
+use serde::{de, Deserializer, Serializer};
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
 use std::str::FromStr;
 
 pub fn deserialize_from_opt_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -8,14 +10,61 @@ where
     T: FromStr,
     <T as FromStr>::Err: Display,
 {
-    match StringOrFloat::deserialize(deserializer)? {
-        StringOrFloat::Str(s) => match s {
+    deserializer.deserialize_any(OptStrVisitor(PhantomData))
+}
+
+/// Parses strings with `FromStr`, empty strings and nulls are `None`.
+/// Numbers are parsed from their string form, e.g. `seqId` and `checksum` of order book push data.
+struct OptStrVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for OptStrVisitor<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string, a number or null")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        match s {
             "" => Ok(None),
-            s => FromStr::from_str(s)
-                .map_err(de::Error::custom)
-                .map(Option::Some),
-        },
-        _ => Ok(None),
+            s => FromStr::from_str(s).map_err(E::custom).map(Option::Some),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, i: i64) -> Result<Self::Value, E> {
+        self.visit_str(&i.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, u: u64) -> Result<Self::Value, E> {
+        self.visit_str(&u.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, f: f64) -> Result<Self::Value, E> {
+        self.visit_str(&f.to_string())
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    // serde_json with `arbitrary_precision` hands numbers over as a single entry map
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        match map.next_entry::<de::IgnoredAny, String>()? {
+            Some((_, number)) => self.visit_str(&number),
+            None => Ok(None),
+        }
     }
 }
 
@@ -278,20 +327,9 @@ pub type MaybeU64 = Option<u64>;
 pub type MaybeI64 = Option<i64>;
 pub type MaybeString = Option<String>;
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-#[allow(dead_code)]
-enum StringOrFloat<'a> {
-    Str(&'a str),
-    Float(f64),
-    Integer(i64),
-    Bool(bool),
-    Null(()),
-}
-
 #[cfg(test)]
 mod tests_maybe_float {
-    use super::{str_opt, MaybeFloat};
+    use super::{str_opt, MaybeFloat, MaybeString};
     use serde::{Deserialize, Serialize};
 
     #[test]
@@ -323,6 +361,58 @@ mod tests_maybe_float {
         assert_eq!(m.bar, None);
     }
 
+    #[test]
+    fn can_deser_numbers() {
+        #[derive(Debug, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Foo {
+            #[serde(default, with = "str_opt")]
+            seq_id: Option<i64>,
+            #[serde(default, with = "str_opt")]
+            checksum: Option<i64>,
+            #[serde(default, with = "str_opt")]
+            bar: MaybeFloat,
+        }
+        let s = r#"{
+            "seqId": 1234567890123,
+            "checksum": -855196043,
+            "bar": 1.5
+        }"#;
+        let m = serde_json::from_str::<Foo>(s).unwrap();
+        assert_eq!(m.seq_id, Some(1234567890123));
+        assert_eq!(m.checksum, Some(-855196043));
+        assert_eq!(m.bar, Some(1.5));
+    }
+
+    #[test]
+    fn can_deser_numbers_as_strings() {
+        #[derive(Debug, Deserialize)]
+        struct Foo {
+            #[serde(default, with = "str_opt")]
+            bar: MaybeString,
+        }
+        let m = serde_json::from_str::<Foo>(r#"{ "bar": 18446744073709551615 }"#).unwrap();
+        assert_eq!(m.bar.as_deref(), Some("18446744073709551615"));
+        let m = serde_json::from_str::<Foo>(r#"{ "bar": -1 }"#).unwrap();
+        assert_eq!(m.bar.as_deref(), Some("-1"));
+        let m = serde_json::from_str::<Foo>(r#"{ "bar": true }"#).unwrap();
+        assert_eq!(m.bar, None);
+    }
+
+    #[test]
+    fn cannot_deser_invalid_number() {
+        #[derive(Debug, Deserialize)]
+        struct Foo {
+            #[serde(default, with = "str_opt")]
+            bar: Option<i64>,
+        }
+        let m = serde_json::from_str::<Foo>(r#"{ "bar": 15 }"#).unwrap();
+        assert_eq!(m.bar, Some(15));
+        assert!(serde_json::from_str::<Foo>(r#"{ "bar": "1.5x" }"#).is_err());
+        // floats do not parse as integers
+        assert!(serde_json::from_str::<Foo>(r#"{ "bar": 1.5 }"#).is_err());
+    }
+
     #[test]
     fn can_deser_maybe_float_null() {
         #[derive(Debug, Deserialize)]
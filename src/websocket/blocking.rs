@@ -0,0 +1,388 @@
+//! Websocket session for blocking consumers, usable without an async runtime.
+//!
+//! [`WsSession`] complements [`WsClient`](crate::websocket::client::WsClient) the way
+//! [`api::blocking::Rest`](crate::api::blocking::Rest) complements [`api::Rest`](crate::api::Rest):
+//! it logs in, keeps the connection alive with `ping` and parses frames on the calling thread.
+//! Frames are read with the blocking [`WsSession::read`], or polled with [`WsSession::poll`]
+//! from a busy loop once the session is switched to non-blocking.
+//!
+//! ```no_run
+//! use okx_rs::api::v5::ws_convert::{ChannelEvent, WsMessage};
+//! use okx_rs::api::Options;
+//! use okx_rs::api::Production;
+//! use okx_rs::websocket::blocking::WsSession;
+//! use okx_rs::websocket::conn::Books5;
+//!
+//! let options = Options::new(Production);
+//! let mut session = WsSession::connect(options.public_websocket()).unwrap();
+//! session
+//!     .subscribe(&Books5 {
+//!         inst_id: "BTC-USDT".into(),
+//!     })
+//!     .unwrap();
+//! session.set_nonblocking(true).unwrap();
+//! loop {
+//!     if let Some(WsMessage::Channel(ChannelEvent::Books5(books))) = session.poll_message().unwrap() {
+//!         println!("{:?}", books.data);
+//!     }
+//! }
+//! ```
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::api::error::{ApiError, Error};
+use crate::api::v5::ws_convert::{ControlEvent, WsMessage};
+use crate::api::Options;
+use crate::websocket::capture::{now_micros, Capture};
+use crate::websocket::subscription::Subscriptions;
+use crate::websocket::{OKXAuth, WebsocketChannel, DEFAULT_REQUEST_TIMEOUT, HEARTBEAT_INTERVAL};
+
+/// Read timeout of a blocking session, bounds how late `ping` is sent while waiting for frames
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub struct WsSession {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    timeout: Duration,
+    nonblocking: bool,
+    last_recv: Instant,
+    last_ping: Instant,
+    /// Last text frame read, borrowed by [`WsSession::read`] and [`WsSession::poll`]
+    frame: String,
+    recv_ts: u64,
+    /// Records received frames with the connection id
+    capture: Option<(Capture, String)>,
+}
+
+impl WsSession {
+    /// Connects to `url` in blocking mode
+    pub fn connect(url: &str) -> Result<Self, Error<()>> {
+        let (socket, _) = tungstenite::connect(url)?;
+        let now = Instant::now();
+        let session = Self {
+            socket,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            nonblocking: false,
+            last_recv: now,
+            last_ping: now,
+            frame: String::new(),
+            recv_ts: 0,
+            capture: None,
+        };
+        if let Some(stream) = session.tcp_stream() {
+            stream
+                .set_read_timeout(Some(READ_TIMEOUT))
+                .map_err(tungstenite::Error::Io)?;
+        }
+        Ok(session)
+    }
+
+    /// Connects to the private endpoint of `options` and logs in
+    pub fn connect_private(options: &Options) -> Result<Self, Error<()>> {
+        let mut session = Self::connect(options.private_websocket())?;
+        session.login(options)?;
+        Ok(session)
+    }
+
+    /// Timeout applied by [`WsSession::login`]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Records every text frame received from now on to `capture`,
+    /// stamped with its local receive time and `conn_id`
+    pub fn with_capture(mut self, capture: Capture, conn_id: impl Into<String>) -> Self {
+        self.capture = Some((capture, conn_id.into()));
+        self
+    }
+
+    /// Switches between blocking reads and non-blocking polling of the underlying socket.
+    /// Outgoing messages that cannot be written at once are flushed by later reads.
+    pub fn set_nonblocking(&mut self, nonblocking: bool) -> Result<(), Error<()>> {
+        if let Some(stream) = self.tcp_stream() {
+            stream
+                .set_nonblocking(nonblocking)
+                .map_err(tungstenite::Error::Io)?;
+        }
+        self.nonblocking = nonblocking;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking
+    }
+
+    /// Sends the login request and waits for its result.
+    /// Frames received before the login result are discarded.
+    pub fn login(&mut self, options: &Options) -> Result<(), Error<()>> {
+        if options.passphrase.is_none() {
+            return Err(Error::NoSecretConfigured);
+        }
+        let auth = OKXAuth::ws_auth(options.clone()).map_err(|_| Error::NoSecretConfigured)?;
+        self.send(auth)?;
+
+        let deadline = Instant::now() + self.timeout;
+        while Instant::now() < deadline {
+            let Some(text) = self.poll()? else {
+                if self.nonblocking {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                continue;
+            };
+            match WsMessage::try_parse(text) {
+                Ok(Some(WsMessage::Control(ControlEvent::LoginOk { .. }))) => return Ok(()),
+                Ok(Some(WsMessage::Control(ControlEvent::Error { code, msg, conn_id }))) => {
+                    return Err(Error::Api(ApiError {
+                        code,
+                        msg,
+                        data: None,
+                        conn_id,
+                    }))
+                }
+                _ => continue,
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    pub fn send(&mut self, msg: impl Into<String>) -> Result<(), Error<()>> {
+        match self.socket.send(Message::Text(msg.into())) {
+            // queued, written by the next flush
+            Err(err) if would_block(&err) => Ok(()),
+            result => Ok(result?),
+        }
+    }
+
+    pub fn subscribe<C: WebsocketChannel>(&mut self, channel: &C) -> Result<(), Error<()>> {
+        self.send(channel.subscribe_message())
+    }
+
+    pub fn unsubscribe<C: WebsocketChannel>(&mut self, channel: &C) -> Result<(), Error<()>> {
+        self.send(channel.unsubscribe_message())
+    }
+
+    pub fn subscribe_all(&mut self, subscriptions: &Subscriptions) -> Result<(), Error<()>> {
+        for msg in subscriptions.subscribe_messages() {
+            self.send(msg)?;
+        }
+        Ok(())
+    }
+
+    pub fn unsubscribe_all(&mut self, subscriptions: &Subscriptions) -> Result<(), Error<()>> {
+        for msg in subscriptions.unsubscribe_messages() {
+            self.send(msg)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next text frame, to be parsed with [`WsMessage::try_parse`].
+    /// In non-blocking mode, this spins until a frame is available.
+    pub fn read(&mut self) -> Result<&str, Error<()>> {
+        while !self.next_frame()? {
+            if self.nonblocking {
+                std::hint::spin_loop();
+            }
+        }
+        Ok(&self.frame)
+    }
+
+    /// Reads a text frame if one is available.
+    /// In blocking mode, this waits up to one second for a frame.
+    pub fn poll(&mut self) -> Result<Option<&str>, Error<()>> {
+        Ok(self.next_frame()?.then_some(self.frame.as_str()))
+    }
+
+    /// [`WsSession::poll`] and parses the frame with [`WsMessage::try_parse`]
+    /// (or `try_parse_simd` with the `simd` feature)
+    pub fn poll_message(&mut self) -> Result<Option<WsMessage<'_>>, Error<()>> {
+        if !self.next_frame()? {
+            return Ok(None);
+        }
+        #[cfg(not(feature = "simd"))]
+        let message = WsMessage::try_parse(&self.frame);
+        #[cfg(feature = "simd")]
+        let message = WsMessage::try_parse_simd(&mut self.frame);
+        message
+    }
+
+    /// Local receive time of the last frame read in µs since the unix epoch, see [`now_micros`]
+    #[inline]
+    pub fn recv_ts(&self) -> u64 {
+        self.recv_ts
+    }
+
+    pub fn close(&mut self) -> Result<(), Error<()>> {
+        match self.socket.close(None) {
+            Err(err) if would_block(&err) => Ok(()),
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => Ok(()),
+            result => Ok(result?),
+        }
+    }
+
+    /// Sends `ping` when nothing was received for [`HEARTBEAT_INTERVAL`], then reads one message.
+    /// Returns whether a text frame other than `pong` was stored in `self.frame`.
+    fn next_frame(&mut self) -> Result<bool, Error<()>> {
+        if self.last_recv.elapsed() >= HEARTBEAT_INTERVAL
+            && self.last_ping.elapsed() >= HEARTBEAT_INTERVAL
+        {
+            self.last_ping = Instant::now();
+            self.send("ping")?;
+        }
+        match self.socket.read() {
+            Ok(Message::Text(text)) => {
+                let recv_ts = now_micros();
+                self.last_recv = Instant::now();
+                if text == "pong" {
+                    return Ok(false);
+                }
+                if let Some((capture, conn_id)) = &self.capture {
+                    if let Err(err) = capture.record_at(recv_ts, conn_id, &text) {
+                        log::error!("failed to capture frame: {}", err);
+                    }
+                }
+                self.recv_ts = recv_ts;
+                self.frame = text;
+                Ok(true)
+            }
+            Ok(Message::Close(frame)) => {
+                log::info!("websocket closed: {:?}", frame);
+                Err(Error::ConnectionClosed)
+            }
+            Ok(_) => {
+                self.last_recv = Instant::now();
+                Ok(false)
+            }
+            Err(err) if would_block(&err) => Ok(false),
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                Err(Error::ConnectionClosed)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn tcp_stream(&self) -> Option<&TcpStream> {
+        match self.socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => Some(stream),
+            MaybeTlsStream::NativeTls(stream) => Some(stream.get_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a read or write hit the read timeout or would block in non-blocking mode
+fn would_block(err: &tungstenite::Error) -> bool {
+    matches!(err, tungstenite::Error::Io(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut))
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests_blocking {
+    use std::time::{Duration, Instant};
+
+    use super::WsSession;
+    use crate::api::error::Error;
+    use crate::api::v5::ws_convert::{ChannelEvent, WsMessage};
+    use crate::api::Options;
+    use crate::websocket::capture::{Capture, Replay};
+    use crate::websocket::conn::Books;
+    use crate::websocket::mock::{BookFeed, MockServer};
+    use crate::websocket::WebsocketChannel;
+
+    #[test]
+    fn test_blocking_session() {
+        let mut feed = BookFeed::new(Books::CHANNEL, "BTC-USDT");
+        let mut events = vec![feed.snapshot(&[("101", "1")], &[("99", "1")])];
+        for _ in 0..5 {
+            events.push(feed.update(&[("101", "2")], &[]));
+        }
+        let last_seq_id = feed.seq_id();
+        let server = MockServer::builder()
+            .credential("key", "secret", "passphrase")
+            .feed(Books::CHANNEL, events)
+            .bind("127.0.0.1:0")
+            .unwrap();
+
+        let wrong = Options::new_with(server.env(), "key", "wrong secret", "passphrase");
+        assert!(matches!(
+            WsSession::connect_private(&wrong),
+            Err(Error::Api(_))
+        ));
+
+        let options = Options::new_with(server.env(), "key", "secret", "passphrase");
+        let mut session = WsSession::connect_private(&options).unwrap();
+        session
+            .subscribe(&Books {
+                inst_id: "BTC-USDT".into(),
+            })
+            .unwrap();
+        // subscribe ack, then the feed
+        assert!(session.read().unwrap().contains(r#""event":"subscribe""#));
+
+        session.set_nonblocking(true).unwrap();
+        let mut seq_ids = vec![];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while seq_ids.last() != Some(&last_seq_id) && Instant::now() < deadline {
+            if let Some(WsMessage::Channel(ChannelEvent::Books(response))) =
+                session.poll_message().unwrap()
+            {
+                seq_ids.push(response.data.unwrap()[0].seq_id.unwrap());
+            }
+        }
+        assert_eq!(seq_ids, (last_seq_id - 5..=last_seq_id).collect::<Vec<_>>());
+        assert!(session.recv_ts() > 0);
+        // nothing left to read
+        assert!(session.poll().unwrap().is_none());
+
+        session.set_nonblocking(false).unwrap();
+        session.send("ping").unwrap();
+        // `pong` is consumed by the session
+        assert!(session.poll().unwrap().is_none());
+        session.close().unwrap();
+    }
+
+    #[test]
+    fn test_session_capture() {
+        let mut feed = BookFeed::new(Books::CHANNEL, "BTC-USDT");
+        let server = MockServer::builder()
+            .feed(
+                Books::CHANNEL,
+                [feed.snapshot(&[("101", "1")], &[("99", "1")])],
+            )
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let path =
+            std::env::temp_dir().join(format!("okx-rs-{}-session-capture", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let capture = Capture::open(&path).unwrap();
+        let mut session = WsSession::connect(&server.url())
+            .unwrap()
+            .with_capture(capture.clone(), "public-1");
+        session
+            .subscribe(&Books {
+                inst_id: "BTC-USDT".into(),
+            })
+            .unwrap();
+        let ack = session.read().unwrap().to_string();
+        let snapshot = session.read().unwrap().to_string();
+        capture.flush().unwrap();
+
+        let frames = Replay::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.conn_id.as_str(), frame.frame.as_str()))
+                .collect::<Vec<_>>(),
+            [("public-1", ack.as_str()), ("public-1", snapshot.as_str())]
+        );
+        assert_eq!(frames[1].ts, session.recv_ts());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+//! Capture of raw websocket frames to disk, and replay through the message router.
+//!
+//! Frames are appended one per line as `<receive ts in µs>\t<conn id>\t<frame>`,
+//! so captures of several connections can share a file and be inspected with `grep` / `cut`.
+//!
+//! ```no_run
+//! use okx_rs::websocket::capture::{Capture, Replay};
+//!
+//! let capture = Capture::open("books.capture").unwrap();
+//! capture.record("public-1", r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[]}"#).unwrap();
+//! capture.flush().unwrap();
+//!
+//! // replay 10 times faster than captured
+//! Replay::open("books.capture")
+//!     .unwrap()
+//!     .speed(10.0)
+//!     .route(|ts, conn_id, message| println!("{ts} {conn_id} {:?}", message))
+//!     .unwrap();
+//! ```
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::api::error::Error;
+use crate::api::v5::ws_convert::WsMessage;
+
+/// Local time in microseconds since the unix epoch
+pub fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64
+}
+
+/// Append-only capture file. Clones share the same file and can be handed to several connections.
+///
+/// Frames are written by a background thread, so that recording never blocks the receive loop on
+/// file I/O. Buffered frames are written when the last clone is dropped or on [`Capture::flush`].
+#[derive(Clone)]
+pub struct Capture {
+    writer: Arc<CaptureWriter>,
+}
+
+enum WriterCommand {
+    Line(String),
+    Flush(mpsc::Sender<io::Result<()>>),
+}
+
+/// Sender to the writer thread, joined on drop so that every frame is on disk afterwards
+struct CaptureWriter {
+    commands: Option<mpsc::Sender<WriterCommand>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for CaptureWriter {
+    fn drop(&mut self) {
+        // closing the channel stops the thread once it has written every line
+        self.commands.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Capture {
+    /// Opens `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (commands, commands_rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("okx-capture".into())
+            .spawn(move || write_lines(BufWriter::new(file), commands_rx))?;
+        Ok(Self {
+            writer: Arc::new(CaptureWriter {
+                commands: Some(commands),
+                thread: Some(thread),
+            }),
+        })
+    }
+
+    /// Records `frame` received now on connection `conn_id`
+    #[inline]
+    pub fn record(&self, conn_id: &str, frame: &str) -> io::Result<()> {
+        self.record_at(now_micros(), conn_id, frame)
+    }
+
+    /// Records `frame` received at `ts` (µs since the unix epoch) on connection `conn_id`.
+    /// Only formats the line, writing it is left to the writer thread.
+    ///
+    /// Line breaks are not valid inside JSON strings, line breaks in `frame` are written as spaces.
+    /// Tabs and line breaks in `conn_id` are written as `_`.
+    pub fn record_at(&self, ts: u64, conn_id: &str, frame: &str) -> io::Result<()> {
+        let mut line = String::with_capacity(frame.len() + conn_id.len() + 24);
+        // writing to a String does not fail
+        let _ = write!(line, "{ts}\t");
+        if conn_id.contains(['\t', '\n', '\r']) {
+            line.push_str(&conn_id.replace(['\t', '\n', '\r'], "_"));
+        } else {
+            line.push_str(conn_id);
+        }
+        line.push('\t');
+        if frame.contains(['\n', '\r']) {
+            line.push_str(&frame.replace(['\n', '\r'], " "));
+        } else {
+            line.push_str(frame);
+        }
+        line.push('\n');
+        self.send(WriterCommand::Line(line))
+    }
+
+    /// Waits until every frame recorded so far is written to the file.
+    /// Returns the first write error since the last flush.
+    pub fn flush(&self) -> io::Result<()> {
+        let (result, result_rx) = mpsc::channel();
+        self.send(WriterCommand::Flush(result))?;
+        result_rx.recv().map_err(|_| writer_stopped())?
+    }
+
+    fn send(&self, command: WriterCommand) -> io::Result<()> {
+        match &self.writer.commands {
+            Some(commands) => commands.send(command).map_err(|_| writer_stopped()),
+            None => Err(writer_stopped()),
+        }
+    }
+}
+
+fn writer_stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "capture writer stopped")
+}
+
+/// Writer thread, runs until every [`Capture`] clone is dropped
+fn write_lines(mut writer: BufWriter<File>, commands: mpsc::Receiver<WriterCommand>) {
+    let mut error = None;
+    for command in commands {
+        match command {
+            WriterCommand::Line(line) => {
+                if let Err(err) = writer.write_all(line.as_bytes()) {
+                    log::error!("failed to capture frame: {}", err);
+                    error.get_or_insert(err);
+                }
+            }
+            WriterCommand::Flush(result) => {
+                let flushed = match error.take() {
+                    Some(err) => Err(err),
+                    None => writer.flush(),
+                };
+                let _ = result.send(flushed);
+            }
+        }
+    }
+    if let Err(err) = writer.flush() {
+        log::error!("failed to flush capture: {}", err);
+    }
+}
+
+/// Frame read back from a capture file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    /// Local receive time in µs since the unix epoch
+    pub ts: u64,
+    pub conn_id: String,
+    pub frame: String,
+}
+
+impl CapturedFrame {
+    fn parse_line(line: &str) -> io::Result<Self> {
+        let mut fields = line.splitn(3, '\t');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(ts), Some(conn_id), Some(frame)) => Ok(CapturedFrame {
+                ts: ts
+                    .parse()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+                conn_id: conn_id.to_string(),
+                frame: frame.to_string(),
+            }),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid capture line: {line}"),
+            )),
+        }
+    }
+}
+
+/// Reads a capture file in order, pacing frames by their receive timestamps.
+pub struct Replay {
+    lines: Lines<BufReader<File>>,
+    speed: f64,
+    start: Option<(u64, Instant)>,
+}
+
+impl Replay {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            lines: BufReader::new(File::open(path)?).lines(),
+            speed: f64::INFINITY,
+            start: None,
+        })
+    }
+
+    /// Replay speed relative to capture time, `1.0` reproduces the original gaps between frames.
+    /// Defaults to `f64::INFINITY`, replaying without waiting.
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Parses every frame with [`WsMessage::try_parse`] (or `try_parse_simd` with the `simd` feature),
+    /// the same path as live connections, and hands the result to `f`.
+    pub fn route<F>(self, mut f: F) -> io::Result<()>
+    where
+        F: FnMut(u64, &str, Result<Option<WsMessage<'_>>, Error<()>>),
+    {
+        for frame in self {
+            let CapturedFrame {
+                ts,
+                conn_id,
+                #[allow(unused_mut)]
+                mut frame,
+            } = frame?;
+            #[cfg(not(feature = "simd"))]
+            let message = WsMessage::try_parse(&frame);
+            #[cfg(feature = "simd")]
+            let message = WsMessage::try_parse_simd(&mut frame);
+            f(ts, &conn_id, message);
+        }
+        Ok(())
+    }
+
+    fn wait(&mut self, ts: u64) {
+        if !self.speed.is_finite() || self.speed <= 0.0 {
+            return;
+        }
+        let (first_ts, started) = *self.start.get_or_insert((ts, Instant::now()));
+        let offset = Duration::from_micros(ts.saturating_sub(first_ts)).div_f64(self.speed);
+        let elapsed = started.elapsed();
+        if offset > elapsed {
+            std::thread::sleep(offset - elapsed);
+        }
+    }
+}
+
+impl Iterator for Replay {
+    type Item = io::Result<CapturedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            match self.lines.next()? {
+                Ok(line) if line.is_empty() => continue,
+                Ok(line) => break line,
+                Err(err) => return Some(Err(err)),
+            }
+        };
+        let frame = CapturedFrame::parse_line(&line);
+        if let Ok(frame) = &frame {
+            self.wait(frame.ts);
+        }
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests_capture {
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    use super::{Capture, CapturedFrame, Replay};
+    use crate::api::v5::ws_convert::{ChannelEvent, ControlEvent, WsMessage};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("okx-rs-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    const BOOKS5: &str = r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[["8476.98","415","0","13"]],"bids":[["8476.97","256","0","12"]],"instId":"BTC-USDT","ts":"1597026383085","seqId":123456}]}"#;
+    const SUBSCRIBE: &str = r#"{"event":"subscribe","arg":{"channel":"books5","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#;
+
+    #[test]
+    fn test_capture_and_route() {
+        let path = temp_path("route");
+        let capture = Capture::open(&path).unwrap();
+        capture.record_at(1_000, "public\t1", SUBSCRIBE).unwrap();
+        capture
+            .clone()
+            .record_at(2_000, "public-2", &format!("{BOOKS5}\n"))
+            .unwrap();
+        capture.record_at(3_000, "public-2", "not json").unwrap();
+        drop(capture);
+
+        // appending keeps earlier frames
+        let capture = Capture::open(&path).unwrap();
+        capture.record_at(4_000, "public-2", BOOKS5).unwrap();
+        capture.flush().unwrap();
+
+        let frames = Replay::open(&path)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(
+            frames[0],
+            CapturedFrame {
+                ts: 1_000,
+                conn_id: "public_1".into(),
+                frame: SUBSCRIBE.into(),
+            }
+        );
+
+        let mut routed = vec![];
+        Replay::open(&path)
+            .unwrap()
+            .route(|ts, conn_id, message| {
+                let kind = match message {
+                    Ok(Some(WsMessage::Control(ControlEvent::SubscribeAck { .. }))) => "ack",
+                    Ok(Some(WsMessage::Channel(ChannelEvent::Books5(_)))) => "books5",
+                    Err(_) => "error",
+                    other => panic!("unexpected message: {:?}", other),
+                };
+                routed.push((ts, conn_id.to_string(), kind));
+            })
+            .unwrap();
+        assert_eq!(
+            routed,
+            vec![
+                (1_000, "public_1".to_string(), "ack"),
+                (2_000, "public-2".to_string(), "books5"),
+                (3_000, "public-2".to_string(), "error"),
+                (4_000, "public-2".to_string(), "books5"),
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_speed() {
+        let path = temp_path("speed");
+        let capture = Capture::open(&path).unwrap();
+        capture.record_at(0, "c", BOOKS5).unwrap();
+        capture.record_at(200_000, "c", BOOKS5).unwrap();
+        drop(capture);
+
+        let started = Instant::now();
+        assert_eq!(Replay::open(&path).unwrap().speed(4.0).count(), 2);
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(50), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(200), "{:?}", elapsed);
+
+        let started = Instant::now();
+        assert_eq!(Replay::open(&path).unwrap().count(), 2);
+        assert!(started.elapsed() < Duration::from_millis(50));
+        std::fs::remove_file(path).unwrap();
+    }
+}
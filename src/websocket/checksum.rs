@@ -0,0 +1,99 @@
+//! Order book checksum of `books` push data.
+//!
+//! OKX joins the first 25 bid and ask levels as `bid1Px:bid1Sz:ask1Px:ask1Sz:bid2Px:...`,
+//! continuing with the remaining levels of the deeper side, and sends the CRC32 of that string
+//! as a signed 32 bit integer. Prices and sizes are taken as pushed, e.g. `"0.10"` and not `"0.1"`.
+
+use std::fmt::{Display, Write};
+
+/// Levels of each side included in the checksum
+pub const CHECKSUM_DEPTH: usize = 25;
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC32 (IEEE) of `bytes`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Checksum of a book given its `(price, size)` levels from the best price
+pub fn book_checksum<P, S>(
+    bids: impl IntoIterator<Item = (P, S)>,
+    asks: impl IntoIterator<Item = (P, S)>,
+) -> i32
+where
+    P: Display,
+    S: Display,
+{
+    let mut bids = bids.into_iter().take(CHECKSUM_DEPTH);
+    let mut asks = asks.into_iter().take(CHECKSUM_DEPTH);
+    let mut buf = String::with_capacity(CHECKSUM_DEPTH * 2 * 24);
+    loop {
+        let (bid, ask) = (bids.next(), asks.next());
+        if bid.is_none() && ask.is_none() {
+            break;
+        }
+        for (price, size) in bid.into_iter().chain(ask) {
+            if !buf.is_empty() {
+                buf.push(':');
+            }
+            // writing to a String does not fail
+            let _ = write!(buf, "{price}:{size}");
+        }
+    }
+    crc32(buf.as_bytes()) as i32
+}
+
+#[cfg(test)]
+mod tests_checksum {
+    use super::{book_checksum, crc32};
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_book_checksum() {
+        let bids = [("3366.1", "7"), ("3366", "6")];
+        let asks = [("3366.8", "9"), ("3368", "8")];
+        assert_eq!(
+            book_checksum(bids, asks),
+            crc32(b"3366.1:7:3366.8:9:3366:6:3368:8") as i32
+        );
+        // the deeper side continues alone
+        assert_eq!(
+            book_checksum(bids, asks[..1].iter().copied()),
+            crc32(b"3366.1:7:3366.8:9:3366:6") as i32
+        );
+        // only the first 25 levels of each side count
+        let deep = (0..30).map(|i| (100 - i, 1)).collect::<Vec<_>>();
+        assert_eq!(
+            book_checksum(deep.iter().copied(), [(101, 2)]),
+            book_checksum(deep[..25].iter().copied(), [(101, 2)])
+        );
+    }
+}
@@ -0,0 +1,671 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::api::error::{ApiError, Error};
+use crate::api::v5::ws_convert::{ControlEvent, WsMessage};
+use crate::api::v5::ApiResponse;
+use crate::api::Options;
+use crate::websocket::capture::{now_micros, Capture};
+use crate::websocket::subscription::Subscriptions;
+use crate::websocket::{Endpoint, OKXAuth, WebsocketChannel, WebsocketOp};
+
+pub use crate::websocket::{DEFAULT_REQUEST_TIMEOUT, HEARTBEAT_INTERVAL};
+
+type Pending = Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>;
+
+/// Text frames not consumed by the client, to be parsed with [`WsMessage::try_parse`].
+/// The receiver is closed once the connection is closed.
+pub type WsReceiver = mpsc::UnboundedReceiver<String>;
+
+/// Text frame stamped with its local receive time, taken when the connection task reads it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamped {
+    /// Local receive time in µs since the unix epoch, see [`now_micros`]
+    pub recv_ts: u64,
+    pub frame: String,
+}
+
+/// Receiver of [`WsClient::connect_stamped`], frames stamped with their local receive time
+pub type StampedReceiver = mpsc::UnboundedReceiver<Stamped>;
+
+/// Receivers of pushed frames, accepted by [`WsClient::login`]
+pub trait FrameReceiver {
+    fn recv_frame(&mut self) -> impl Future<Output = Option<String>> + Send;
+}
+
+impl FrameReceiver for WsReceiver {
+    async fn recv_frame(&mut self) -> Option<String> {
+        self.recv().await
+    }
+}
+
+impl FrameReceiver for StampedReceiver {
+    async fn recv_frame(&mut self) -> Option<String> {
+        self.recv().await.map(|stamped| stamped.frame)
+    }
+}
+
+enum Incoming {
+    Frames(mpsc::UnboundedSender<String>),
+    Stamped(mpsc::UnboundedSender<Stamped>),
+}
+
+impl Incoming {
+    // keep serving requests even if nobody reads pushed messages
+    fn send(&self, recv_ts: u64, frame: String) {
+        match self {
+            Incoming::Frames(incoming) => {
+                let _ = incoming.send(frame);
+            }
+            Incoming::Stamped(incoming) => {
+                let _ = incoming.send(Stamped { recv_ts, frame });
+            }
+        }
+    }
+}
+
+/// Async websocket connection driven by a background task.
+///
+/// The task answers heartbeats, forwards received text frames to the [`WsReceiver`],
+/// and resolves [`WebsocketOp`] requests by matching the `id` of their responses.
+/// The client is cheap to clone, the connection is closed when all clones are dropped.
+#[derive(Clone)]
+pub struct WsClient {
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: Pending,
+    next_id: Arc<AtomicU64>,
+    timeout: Duration,
+    /// Endpoint of the connection, `None` when connected to a bare url
+    endpoint: Option<Endpoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpHeader<'a> {
+    #[serde(borrow)]
+    id: Option<&'a str>,
+    #[serde(borrow)]
+    op: Option<&'a str>,
+}
+
+impl WsClient {
+    pub async fn connect(url: &str) -> Result<(Self, WsReceiver), Error<()>> {
+        let (incoming, incoming_rx) = mpsc::unbounded_channel();
+        let client = Self::connect_inner(url, Incoming::Frames(incoming), None).await?;
+        Ok((client, incoming_rx))
+    }
+
+    /// Same as [`WsClient::connect`], stamping every frame with its local receive time,
+    /// e.g. to measure latency with [`crate::websocket::latency::LatencyTracker`]
+    pub async fn connect_stamped(url: &str) -> Result<(Self, StampedReceiver), Error<()>> {
+        let (incoming, incoming_rx) = mpsc::unbounded_channel();
+        let client = Self::connect_inner(url, Incoming::Stamped(incoming), None).await?;
+        Ok((client, incoming_rx))
+    }
+
+    /// Connects to `url` and records every received text frame to `capture`,
+    /// stamped with its local receive time and `conn_id`.
+    pub async fn connect_with_capture(
+        url: &str,
+        capture: Capture,
+        conn_id: impl Into<String>,
+    ) -> Result<(Self, WsReceiver), Error<()>> {
+        let (incoming, incoming_rx) = mpsc::unbounded_channel();
+        let capture = Some((capture, conn_id.into()));
+        let client = Self::connect_inner(url, Incoming::Frames(incoming), capture).await?;
+        Ok((client, incoming_rx))
+    }
+
+    async fn connect_inner(
+        url: &str,
+        incoming: Incoming,
+        capture: Option<(Capture, String)>,
+    ) -> Result<Self, Error<()>> {
+        let (stream, _) = tokio_tungstenite::connect_async(url).await?;
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let pending = Pending::default();
+        tokio::spawn(run(stream, outgoing_rx, incoming, pending.clone(), capture));
+
+        // ids only need to be unique per connection, seed with time to also differ across reconnects
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64;
+        Ok(Self {
+            outgoing,
+            pending,
+            next_id: Arc::new(AtomicU64::new(seed)),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            endpoint: None,
+        })
+    }
+
+    /// Connects to the private endpoint and logs in with the credentials in `options`.
+    #[inline]
+    pub async fn connect_private(options: &Options) -> Result<(Self, WsReceiver), Error<()>> {
+        Self::connect_login(options, Endpoint::Private).await
+    }
+
+    /// Connects to `endpoint` and logs in with the credentials in `options`,
+    /// e.g. [`Endpoint::Business`] for ops served there
+    pub async fn connect_login(
+        options: &Options,
+        endpoint: Endpoint,
+    ) -> Result<(Self, WsReceiver), Error<()>> {
+        let (client, mut incoming) = Self::connect(endpoint.url(options)).await?;
+        let client = client.with_endpoint(endpoint);
+        client.login(options, &mut incoming).await?;
+        Ok((client, incoming))
+    }
+
+    /// Opens one connection per [`Subscriptions::connections`] chunk on the endpoint of its channels,
+    /// logs in where a channel requires it, and subscribes.
+    pub async fn connect_subscriptions(
+        options: &Options,
+        subscriptions: &Subscriptions,
+    ) -> Result<Vec<(Self, WsReceiver)>, Error<()>> {
+        let mut clients = Vec::new();
+        for connection in subscriptions.connections() {
+            let endpoint = connection.endpoint().unwrap_or(Endpoint::Public);
+            let (client, mut incoming) = Self::connect(endpoint.url(options)).await?;
+            let client = client.with_endpoint(endpoint);
+            if connection.requires_login() {
+                client.login(options, &mut incoming).await?;
+            }
+            client.subscribe_all(&connection)?;
+            clients.push((client, incoming));
+        }
+        Ok(clients)
+    }
+
+    /// Sends the login request and waits for its result.
+    /// Frames received before the login result are discarded.
+    pub async fn login(
+        &self,
+        options: &Options,
+        incoming: &mut impl FrameReceiver,
+    ) -> Result<(), Error<()>> {
+        if options.passphrase.is_none() {
+            return Err(Error::NoSecretConfigured);
+        }
+        let auth = OKXAuth::ws_auth(options.clone()).map_err(|_| Error::NoSecretConfigured)?;
+        self.send(auth)?;
+
+        let login = async {
+            while let Some(text) = incoming.recv_frame().await {
+                match WsMessage::try_parse(&text) {
+                    Ok(Some(WsMessage::Control(ControlEvent::LoginOk { .. }))) => return Ok(()),
+                    Ok(Some(WsMessage::Control(ControlEvent::Error { code, msg, conn_id }))) => {
+                        return Err(Error::Api(ApiError {
+                            code,
+                            msg,
+                            data: None,
+                            conn_id,
+                        }))
+                    }
+                    _ => continue,
+                }
+            }
+            Err(Error::ConnectionClosed)
+        };
+        tokio::time::timeout(self.timeout, login)
+            .await
+            .map_err(|_| Error::Timeout)?
+    }
+
+    /// Timeout applied by [`WsClient::request`] and [`WsClient::login`]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Endpoint the client is connected to, checked against [`WebsocketOp::ENDPOINT`] by [`WsClient::request`].
+    /// Set by the `connect_*` methods taking [`Options`], clients connected to a bare url are not checked
+    pub fn with_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    pub fn send(&self, msg: impl Into<String>) -> Result<(), Error<()>> {
+        self.outgoing
+            .send(Message::Text(msg.into()))
+            .map_err(|_| Error::ConnectionClosed)
+    }
+
+    pub fn subscribe<C: WebsocketChannel>(&self, channel: &C) -> Result<(), Error<()>> {
+        self.send(channel.subscribe_message())
+    }
+
+    pub fn unsubscribe<C: WebsocketChannel>(&self, channel: &C) -> Result<(), Error<()>> {
+        self.send(channel.unsubscribe_message())
+    }
+
+    pub fn subscribe_all(&self, subscriptions: &Subscriptions) -> Result<(), Error<()>> {
+        for msg in subscriptions.subscribe_messages() {
+            self.send(msg)?;
+        }
+        Ok(())
+    }
+
+    pub fn unsubscribe_all(&self, subscriptions: &Subscriptions) -> Result<(), Error<()>> {
+        for msg in subscriptions.unsubscribe_messages() {
+            self.send(msg)?;
+        }
+        Ok(())
+    }
+
+    /// Sends a trading op and waits for the response carrying the same `id`.
+    ///
+    /// The op has to be sent on a logged in connection to its [`WebsocketOp::ENDPOINT`],
+    /// e.g. [`MassCancel`](crate::api::v5::MassCancel) is only accepted on
+    /// [`Endpoint::Business`], other order ops on [`Endpoint::Private`] as with [`WsClient::connect_private`].
+    /// Ops for another endpoint than the client's are returned as [`Error::WrongEndpoint`] without being sent.
+    ///
+    /// Like [`crate::api::Rest::request`], a non-zero `code` is returned as [`Error::Api`]
+    /// with the per-order `sCode` / `sMsg` results in `data`.
+    #[inline]
+    pub async fn request<O>(&self, op: &O) -> Result<O::Response, Error<O::Response>>
+    where
+        O: WebsocketOp,
+    {
+        self.request_with_timeout(op, self.timeout).await
+    }
+
+    pub async fn request_with_timeout<O>(
+        &self,
+        op: &O,
+        timeout: Duration,
+    ) -> Result<O::Response, Error<O::Response>>
+    where
+        O: WebsocketOp,
+    {
+        match self.endpoint {
+            Some(endpoint) if endpoint != O::ENDPOINT => {
+                return Err(Error::WrongEndpoint {
+                    op: O::OP,
+                    expected: O::ENDPOINT,
+                    actual: endpoint,
+                })
+            }
+            _ => {}
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), tx);
+
+        let msg = op.op_message(&id);
+        log::debug!("{}", msg);
+        if self.outgoing.send(Message::Text(msg)).is_err() {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(Error::ConnectionClosed);
+        }
+
+        let text = match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(text)) => text,
+            Ok(Err(_)) => return Err(Error::ConnectionClosed),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                return Err(Error::Timeout);
+            }
+        };
+
+        match serde_json::from_str::<ApiResponse<O::Response>>(&text) {
+            Ok(ApiResponse {
+                code: Some(0),
+                data: Some(data),
+                ..
+            }) => Ok(data),
+            Ok(ApiResponse { code, msg, data }) => Err(Error::Api(ApiError {
+                code,
+                msg,
+                data,
+                conn_id: None,
+            })),
+            Err(err) => {
+                log::error!("{}", text);
+                Err(Error::Json(err))
+            }
+        }
+    }
+}
+
+/// Hands an op response to the request waiting for its `id`,
+/// returns the frame back if nobody is waiting for it.
+fn resolve_pending(pending: &Pending, text: String) -> Option<String> {
+    // op responses start with their `id`, pushes with `arg` or `event` are passed through unparsed
+    if !text.starts_with(r#"{"id":"#) {
+        return Some(text);
+    }
+    let id = match serde_json::from_str::<OpHeader>(&text) {
+        Ok(OpHeader {
+            id: Some(id),
+            op: Some(_),
+        }) => id,
+        _ => return Some(text),
+    };
+    let sender = pending.lock().unwrap().remove(id);
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(text);
+            None
+        }
+        None => Some(text),
+    }
+}
+
+async fn run(
+    mut stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mut outgoing: mpsc::UnboundedReceiver<Message>,
+    incoming: Incoming,
+    pending: Pending,
+    capture: Option<(Capture, String)>,
+) {
+    let mut heartbeat = interval_at(Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    loop {
+        tokio::select! {
+            msg = stream.next() => {
+                heartbeat.reset();
+                match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let recv_ts = now_micros();
+                        if text == "pong" {
+                            continue;
+                        }
+                        if let Some((capture, conn_id)) = &capture {
+                            if let Err(err) = capture.record_at(recv_ts, conn_id, &text) {
+                                log::error!("failed to capture frame: {}", err);
+                            }
+                        }
+                        if let Some(text) = resolve_pending(&pending, text) {
+                            incoming.send(recv_ts, text);
+                        }
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        log::info!("websocket closed: {:?}", frame);
+                        break;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => {
+                        log::error!("{}", err);
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            msg = outgoing.recv() => match msg {
+                Some(msg) => {
+                    if let Err(err) = stream.send(msg).await {
+                        log::error!("{}", err);
+                        break;
+                    }
+                }
+                None => {
+                    let _ = stream.close(None).await;
+                    break;
+                }
+            },
+            _ = heartbeat.tick() => {
+                if let Err(err) = stream.send(Message::Text("ping".into())).await {
+                    log::error!("{}", err);
+                    break;
+                }
+            }
+        }
+    }
+    // dropping the senders resolves in-flight requests with `Error::ConnectionClosed`
+    pending.lock().unwrap().clear();
+    if let Some((capture, _)) = capture {
+        // flushing waits for the writer thread, and so does dropping the last clone
+        let flushed = tokio::task::spawn_blocking(move || capture.flush()).await;
+        if let Ok(Err(err)) = flushed {
+            log::error!("failed to flush capture: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_ws_client {
+    use std::time::Duration;
+
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::api::error::Error;
+    use crate::api::v5::{CancelOrder, OrderType, PlaceOrder, Side, TradeMode};
+    use crate::websocket::client::WsClient;
+
+    fn place_order(cl_ord_id: &str) -> PlaceOrder {
+        PlaceOrder {
+            inst_id: "BTC-USDT".into(),
+            td_mode: TradeMode::Cash,
+            ccy: None,
+            cl_ord_id: Some(cl_ord_id.into()),
+            tag: None,
+            side: Side::Buy,
+            pos_side: None,
+            ord_type: OrderType::Limit,
+            sz: "1".into(),
+            px: Some("100".into()),
+            reduce_only: None,
+            tgt_ccy: None,
+            ban_amend: None,
+            attach_algo_cl_ord_id: None,
+            tp_trigger_px: None,
+            tp_ord_px: None,
+            sl_trigger_px: None,
+            sl_ord_px: None,
+            tp_trigger_px_type: None,
+            sl_trigger_px_type: None,
+            quick_mgn_type: None,
+            stp_id: None,
+            stp_mode: None,
+        }
+    }
+
+    /// Answers ops in reverse order of arrival, rejects `batch-orders` and ignores `cancel-order`
+    async fn serve(listener: TcpListener) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let mut requests = vec![];
+        while let Some(Ok(Message::Text(text))) = ws.next().await {
+            let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+            requests.push(request);
+            if requests.len() < 3 {
+                continue;
+            }
+            for request in requests.drain(..).rev() {
+                let args = request["args"].as_array().unwrap();
+                let response = match request["op"].as_str().unwrap() {
+                    "order" => serde_json::json!({
+                        "op": "order",
+                        "code": "0",
+                        "msg": "",
+                        "data": [{
+                            "ordId": "1",
+                            "clOrdId": args[0]["clOrdId"],
+                            "tag": "",
+                            "sCode": "0",
+                            "sMsg": ""
+                        }]
+                    }),
+                    "batch-orders" => serde_json::json!({
+                        "op": "batch-orders",
+                        "code": "2",
+                        "msg": "",
+                        "data": args.iter().map(|arg| serde_json::json!({
+                            "ordId": "",
+                            "clOrdId": arg["clOrdId"],
+                            "tag": "",
+                            "sCode": "51008",
+                            "sMsg": "Order failed. Insufficient balance."
+                        })).collect::<Vec<_>>()
+                    }),
+                    _ => continue,
+                };
+                // like OKX, responses start with the `id` of their request
+                let response = response.to_string();
+                let response = format!(r#"{{"id":{},{}"#, request["id"], &response[1..]);
+                ws.send(Message::Text(response)).await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_correlation() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener));
+
+        let (client, _incoming) = WsClient::connect(&url).await.unwrap();
+        let client = client.with_timeout(Duration::from_millis(500));
+
+        let single = place_order("single");
+        let batch = vec![place_order("batch1"), place_order("batch2")];
+        let cancel = CancelOrder {
+            inst_id: "BTC-USDT".into(),
+            ord_id: Some("1".into()),
+            cl_ord_id: None,
+        };
+        let (single, batch, cancel) = tokio::join!(
+            client.request(&single),
+            client.request(&batch),
+            client.request(&cancel),
+        );
+
+        let single = single.unwrap();
+        assert_eq!(single[0].cl_ord_id.as_deref(), Some("single"));
+
+        match batch {
+            Err(Error::Api(err)) => {
+                assert_eq!(err.code, Some(2));
+                let data = err.data.unwrap();
+                assert_eq!(data.len(), 2);
+                assert_eq!(data[1].cl_ord_id.as_deref(), Some("batch2"));
+                assert_eq!(data[1].s_code, Some(51008));
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+
+        assert!(matches!(cancel, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_request_wrong_endpoint() {
+        use crate::api::v5::{InstrumentType, MassCancel};
+        use crate::websocket::Endpoint;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener));
+
+        let (client, _incoming) = WsClient::connect(&url).await.unwrap();
+        let client = client.with_endpoint(Endpoint::Private);
+        let mass_cancel = MassCancel {
+            inst_type: InstrumentType::Option,
+            inst_family: "BTC-USD".into(),
+            lock_interval: None,
+        };
+        assert!(matches!(
+            client.request(&mass_cancel).await,
+            Err(Error::WrongEndpoint {
+                op: "mass-cancel",
+                expected: Endpoint::Business,
+                actual: Endpoint::Private,
+            })
+        ));
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_connect_subscriptions() {
+        use crate::api::v5::websocket::OrdersChannel;
+        use crate::api::v5::ws_convert::{ControlEvent, WsMessage};
+        use crate::api::v5::{Candles, InstrumentType};
+        use crate::api::Options;
+        use crate::websocket::conn::Books;
+        use crate::websocket::mock::MockServer;
+        use crate::websocket::subscription::Subscriptions;
+
+        let server = MockServer::builder()
+            .credential("key", "secret", "passphrase")
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let options = Options::new_with(server.env(), "key", "secret", "passphrase");
+
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.add(&Books {
+            inst_id: "BTC-USDT".into(),
+        });
+        subscriptions.add(&Candles {
+            bar: "1m".into(),
+            inst_id: "BTC-USDT".into(),
+        });
+        subscriptions.add(&OrdersChannel(InstrumentType::Any));
+
+        let clients = WsClient::connect_subscriptions(&options, &subscriptions)
+            .await
+            .unwrap();
+        assert_eq!(clients.len(), 3);
+        for (_client, mut incoming) in clients {
+            let text = incoming.recv().await.unwrap();
+            assert!(matches!(
+                WsMessage::try_parse(&text),
+                Ok(Some(WsMessage::Control(ControlEvent::SubscribeAck { .. })))
+            ));
+        }
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_connect_stamped() {
+        use crate::api::v5::ws_convert::ChannelEvent;
+        use crate::api::Options;
+        use crate::websocket::capture::now_micros;
+        use crate::websocket::conn::Books5;
+        use crate::websocket::latency::LatencyTracker;
+        use crate::websocket::mock::{BookFeed, MockServer};
+        use crate::websocket::WebsocketChannel;
+
+        let mut feed = BookFeed::new(Books5::CHANNEL, "BTC-USDT");
+        let server = MockServer::builder()
+            .credential("key", "secret", "passphrase")
+            .feed(
+                Books5::CHANNEL,
+                vec![feed.snapshot(&[("100", "1")], &[("99", "1")])],
+            )
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let options = Options::new_with(server.env(), "key", "secret", "passphrase");
+
+        let before = now_micros();
+        let (client, mut incoming) = WsClient::connect_stamped(options.private_websocket())
+            .await
+            .unwrap();
+        client.login(&options, &mut incoming).await.unwrap();
+        client
+            .subscribe(&Books5 {
+                inst_id: "BTC-USDT".into(),
+            })
+            .unwrap();
+
+        let tracker = LatencyTracker::new(10);
+        while tracker.stats(Books5::CHANNEL).is_none() {
+            let stamped = incoming.recv().await.unwrap();
+            assert!(stamped.recv_ts >= before && stamped.recv_ts <= now_micros());
+            if let Ok(Some(event)) = ChannelEvent::try_parse(&stamped.frame) {
+                tracker.observe(&event, stamped.recv_ts);
+            }
+        }
+    }
+}
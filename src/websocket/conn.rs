@@ -45,22 +45,12 @@ impl WebsocketChannel for Books {
     type Response<'de> = [BookUpdate<'de>; 1];
     type ArgType<'de> = BookChannelArg<'de>;
 
-    fn subscribe_message(&self) -> String {
+    fn subscribe_arg(&self) -> serde_json::Value {
         let Books { inst_id } = self;
         json!({
-            "op": "subscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                    "instId": inst_id,
-                }
-            ]
+            "channel": Self::CHANNEL,
+            "instId": inst_id,
         })
-        .to_string()
-    }
-
-    fn unsubscribe_message(&self) -> String {
-        todo!()
     }
 }
 
@@ -69,22 +59,12 @@ impl WebsocketChannel for Books5 {
     type Response<'de> = [BookUpdate<'de>; 1];
     type ArgType<'de> = BookChannelArg<'de>;
 
-    fn subscribe_message(&self) -> String {
+    fn subscribe_arg(&self) -> serde_json::Value {
         let Books5 { inst_id } = self;
         json!({
-            "op": "subscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                    "instId": inst_id,
-                }
-            ]
+            "channel": Self::CHANNEL,
+            "instId": inst_id,
         })
-        .to_string()
-    }
-
-    fn unsubscribe_message(&self) -> String {
-        todo!()
     }
 }
 
@@ -93,22 +73,12 @@ impl WebsocketChannel for BboTbt {
     type Response<'de> = [BookUpdate<'de>; 1];
     type ArgType<'de> = BookChannelArg<'de>;
 
-    fn subscribe_message(&self) -> String {
+    fn subscribe_arg(&self) -> serde_json::Value {
         let BboTbt { inst_id } = self;
         json!({
-            "op": "subscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                    "instId": inst_id,
-                }
-            ]
+            "channel": Self::CHANNEL,
+            "instId": inst_id,
         })
-        .to_string()
-    }
-
-    fn unsubscribe_message(&self) -> String {
-        todo!()
     }
 }
 impl WebsocketChannel for BooksL2Tbt {
@@ -116,21 +86,11 @@ impl WebsocketChannel for BooksL2Tbt {
     type Response<'de> = [BookUpdate<'de>; 1];
     type ArgType<'de> = BookChannelArg<'de>;
 
-    fn subscribe_message(&self) -> String {
+    fn subscribe_arg(&self) -> serde_json::Value {
         let BooksL2Tbt { inst_id } = self;
         json!({
-            "op": "subscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                    "instId": inst_id,
-                }
-            ]
+            "channel": Self::CHANNEL,
+            "instId": inst_id,
         })
-        .to_string()
-    }
-
-    fn unsubscribe_message(&self) -> String {
-        todo!()
     }
 }
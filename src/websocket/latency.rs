@@ -0,0 +1,217 @@
+//! Exchange-to-local latency of websocket messages.
+//!
+//! Latency is the local receive time minus the exchange timestamp carried by the message
+//! (`ts` on order books, `uTime` on orders), corrected by a [`ClockOffset`] between the two clocks.
+//! [`LatencyTracker`] keeps a rolling window of samples per channel and reports percentiles.
+//!
+//! ```
+//! use okx_rs::api::v5::ws_convert::ChannelEvent;
+//! use okx_rs::websocket::capture::now_micros;
+//! use okx_rs::websocket::latency::{ClockOffset, LatencyTracker};
+//!
+//! let offset = ClockOffset::default();
+//! // e.g. around a `GetSystemTime` request: local send time, server time, local receive time
+//! let local_ts = now_micros();
+//! offset.update(local_ts - 2_000, local_ts / 1_000, local_ts);
+//!
+//! let tracker = LatencyTracker::new(1_000).with_offset(offset);
+//! let msg = r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[],"bids":[],"ts":"1597026383085"}]}"#;
+//! let event = ChannelEvent::try_parse(msg).unwrap().unwrap();
+//! tracker.observe(&event, now_micros());
+//! println!("{:?}", tracker.stats("books5"));
+//! ```
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::api::v5::ws_convert::ChannelEvent;
+
+/// Offset in µs to add to the local clock to read the exchange clock.
+/// Clones share the same value, so it can be re-synced while trackers use it.
+#[derive(Debug, Clone, Default)]
+pub struct ClockOffset(Arc<AtomicI64>);
+
+impl ClockOffset {
+    pub fn new(offset_us: i64) -> Self {
+        Self(Arc::new(AtomicI64::new(offset_us)))
+    }
+
+    #[inline]
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn set(&self, offset_us: i64) {
+        self.0.store(offset_us, Ordering::Relaxed)
+    }
+
+    /// Syncs from a request sent at `local_send_us`, answered with the exchange time `exchange_ts_ms`
+    /// and received at `local_recv_us`. The exchange time is assumed to be taken halfway through
+    /// the round trip. Returns the new offset.
+    pub fn update(&self, local_send_us: u64, exchange_ts_ms: u64, local_recv_us: u64) -> i64 {
+        let midpoint = (local_send_us as i64 + local_recv_us as i64) / 2;
+        let offset = exchange_ts_ms as i64 * 1_000 - midpoint;
+        self.set(offset);
+        offset
+    }
+
+    /// One-way latency in µs of a message stamped `exchange_ts_ms` by the exchange and received at `local_recv_us`
+    #[inline]
+    pub fn latency(&self, exchange_ts_ms: u64, local_recv_us: u64) -> i64 {
+        local_recv_us as i64 + self.get() - exchange_ts_ms as i64 * 1_000
+    }
+}
+
+/// Latency percentiles of a channel over the rolling window, in µs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub min: i64,
+    pub p50: i64,
+    pub p90: i64,
+    pub p99: i64,
+    pub max: i64,
+    pub mean: f64,
+}
+
+impl LatencyStats {
+    fn from_samples(samples: &VecDeque<i64>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        // nearest rank
+        let rank =
+            |q: f64| sorted[((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Some(Self {
+            count: sorted.len(),
+            min: sorted[0],
+            p50: rank(0.50),
+            p90: rank(0.90),
+            p99: rank(0.99),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<i64>() as f64 / sorted.len() as f64,
+        })
+    }
+}
+
+/// Rolling latency samples per channel.
+/// Clones share the same samples and can be handed to several connections.
+#[derive(Debug, Clone)]
+pub struct LatencyTracker {
+    window: usize,
+    offset: ClockOffset,
+    samples: Arc<Mutex<HashMap<&'static str, VecDeque<i64>>>>,
+}
+
+impl LatencyTracker {
+    /// Keeps the last `window` samples of every channel
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            offset: ClockOffset::default(),
+            samples: Default::default(),
+        }
+    }
+
+    pub fn with_offset(mut self, offset: ClockOffset) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn offset(&self) -> &ClockOffset {
+        &self.offset
+    }
+
+    /// Records a message of `channel` stamped `exchange_ts_ms` and received at `local_recv_us`,
+    /// returns its latency in µs
+    pub fn record(&self, channel: &'static str, exchange_ts_ms: u64, local_recv_us: u64) -> i64 {
+        let latency = self.offset.latency(exchange_ts_ms, local_recv_us);
+        let mut samples = self.samples.lock().unwrap();
+        let samples = samples.entry(channel).or_default();
+        if samples.len() == self.window {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+        latency
+    }
+
+    /// Records `event` if it carries an exchange timestamp, see [`ChannelEvent::exchange_ts`]
+    pub fn observe(&self, event: &ChannelEvent<'_>, local_recv_us: u64) -> Option<i64> {
+        let exchange_ts = event.exchange_ts()?;
+        Some(self.record(event.channel(), exchange_ts, local_recv_us))
+    }
+
+    pub fn stats(&self, channel: &str) -> Option<LatencyStats> {
+        LatencyStats::from_samples(self.samples.lock().unwrap().get(channel)?)
+    }
+
+    /// Stats of every channel with samples, sorted by channel name
+    pub fn snapshot(&self) -> Vec<(&'static str, LatencyStats)> {
+        let samples = self.samples.lock().unwrap();
+        let mut stats = samples
+            .iter()
+            .filter_map(|(channel, samples)| Some((*channel, LatencyStats::from_samples(samples)?)))
+            .collect::<Vec<_>>();
+        stats.sort_unstable_by_key(|(channel, _)| *channel);
+        stats
+    }
+
+    pub fn clear(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests_latency {
+    use super::{ClockOffset, LatencyTracker};
+    use crate::api::v5::ws_convert::ChannelEvent;
+
+    #[test]
+    fn test_clock_offset() {
+        let offset = ClockOffset::default();
+        // local clock 5ms behind, 2ms round trip
+        assert_eq!(offset.update(1_000_000, 1_006, 1_002_000), 5_000);
+        assert_eq!(offset.clone().get(), 5_000);
+        assert_eq!(offset.latency(1_010, 1_007_000), 2_000);
+    }
+
+    #[test]
+    fn test_rolling_percentiles() {
+        let tracker = LatencyTracker::new(100);
+        for i in 1..=200u64 {
+            tracker.record("books", 0, i * 1_000);
+        }
+        tracker.record("orders", 1, 3_000);
+
+        let stats = tracker.stats("books").unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.min, 101_000);
+        assert_eq!(stats.p50, 150_000);
+        assert_eq!(stats.p90, 190_000);
+        assert_eq!(stats.p99, 199_000);
+        assert_eq!(stats.max, 200_000);
+        assert_eq!(stats.mean, 150_500.0);
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[1].0, "orders");
+        assert_eq!(snapshot[1].1.p99, 2_000);
+        assert!(tracker.stats("tickers").is_none());
+    }
+
+    #[test]
+    fn test_observe() {
+        let tracker = LatencyTracker::new(10).with_offset(ClockOffset::new(-1_000));
+        let msg = r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[],"bids":[],"ts":"1597026383085","seqId":1}]}"#;
+        let event = ChannelEvent::try_parse(msg).unwrap().unwrap();
+        assert_eq!(tracker.observe(&event, 1_597_026_383_090_000), Some(4_000));
+        assert_eq!(tracker.stats("books5").unwrap().count, 1);
+
+        let msg = r#"{"arg":{"channel":"instruments","instType":"SPOT"},"data":[]}"#;
+        let event = ChannelEvent::try_parse(msg).unwrap().unwrap();
+        assert_eq!(tracker.observe(&event, 0), None);
+    }
+}
@@ -0,0 +1,691 @@
+//! Local websocket server speaking the OKX v5 protocol, for testing channel consumers offline.
+//!
+//! The server accepts `login` (verifying the signature against the configured credentials),
+//! acknowledges `subscribe` / `unsubscribe`, answers `ping`, and plays a scripted feed
+//! of [`MockEvent`]s on a connection once one of the feed's channels is subscribed.
+//!
+//! ```no_run
+//! use okx_rs::api::Options;
+//! use okx_rs::websocket::conn::Books;
+//! use okx_rs::websocket::mock::{BookFeed, MockServer};
+//! use okx_rs::websocket::WebsocketChannel;
+//!
+//! let mut feed = BookFeed::new(Books::CHANNEL, "BTC-USDT");
+//! let events = vec![
+//!     feed.snapshot(&[("42000.1", "1")], &[("42000", "2")]),
+//!     feed.update(&[("42000.1", "0")], &[]),
+//! ];
+//! let server = MockServer::builder()
+//!     .credential("key", "secret", "passphrase")
+//!     .feed(Books::CHANNEL, events)
+//!     .bind("127.0.0.1:0")
+//!     .unwrap();
+//! let options = Options::new_with(server.env(), "key", "secret", "passphrase");
+//! ```
+// tungstenite errors only end the mock connection and are not propagated to users
+#![allow(clippy::result_large_err)]
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+
+use crate::api::credential::Credential;
+use crate::api::v5::ws_convert::ChannelEvent;
+use crate::api::OKXEnv;
+use crate::websocket::checksum::book_checksum;
+
+/// How long the server waits on a read before playing pending feed events
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// OKX rejects login requests whose timestamp is more than 30 seconds away from server time
+const LOGIN_TIMESTAMP_TOLERANCE: u64 = 30;
+
+/// Step of a scripted feed
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockEvent {
+    /// Raw text frame, sent as is
+    Message(String),
+    /// Waits before playing the next event
+    Delay(Duration),
+    /// `event: error` frame
+    Error { code: u64, msg: String },
+    /// Drops the connection without a close frame
+    Disconnect,
+}
+
+impl MockEvent {
+    /// Push data frame `{"arg": <arg>, "data": <data>}`, e.g. for `orders`, `positions` or `account`
+    pub fn push(arg: Value, data: Value) -> Self {
+        MockEvent::Message(json!({ "arg": arg, "data": data }).to_string())
+    }
+
+    /// Reads one message per non empty line, e.g. push data recorded from `ws.okx.com`
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Vec<MockEvent>> {
+        let file = std::fs::File::open(path)?;
+        let mut events = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                events.push(MockEvent::Message(line.to_string()));
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Generates `books` style push data with consistent `seqId` / `prevSeqId`
+/// and the `checksum` of the resulting book.
+#[derive(Debug, Clone)]
+pub struct BookFeed {
+    channel: String,
+    inst_id: String,
+    seq_id: i64,
+    prev_seq_id: i64,
+    ts: u64,
+    /// `(price, size)` levels from the best price
+    asks: Vec<(String, String)>,
+    bids: Vec<(String, String)>,
+}
+
+impl BookFeed {
+    pub fn new(channel: &str, inst_id: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            inst_id: inst_id.to_string(),
+            seq_id: 1000,
+            prev_seq_id: -1,
+            ts: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis() as u64,
+            asks: vec![],
+            bids: vec![],
+        }
+    }
+
+    /// `action: snapshot` with the given `(price, size)` levels. Resets `prevSeqId` to -1.
+    pub fn snapshot(&mut self, asks: &[(&str, &str)], bids: &[(&str, &str)]) -> MockEvent {
+        self.prev_seq_id = -1;
+        self.asks.clear();
+        self.bids.clear();
+        self.message("snapshot", asks, bids)
+    }
+
+    /// `action: update` with the given `(price, size)` levels, size `0` removes a level
+    pub fn update(&mut self, asks: &[(&str, &str)], bids: &[(&str, &str)]) -> MockEvent {
+        self.message("update", asks, bids)
+    }
+
+    /// Skips `n` sequence ids, the next update's `prevSeqId` will not match the last `seqId` sent
+    pub fn gap(&mut self, n: i64) {
+        self.seq_id += n;
+        self.prev_seq_id += n;
+    }
+
+    /// `seqId` of the last message
+    pub fn seq_id(&self) -> i64 {
+        self.seq_id
+    }
+
+    /// Checksum of the book after the messages generated so far
+    pub fn checksum(&self) -> i32 {
+        book_checksum(
+            self.bids.iter().map(|(price, size)| (price, size)),
+            self.asks.iter().map(|(price, size)| (price, size)),
+        )
+    }
+
+    fn message(&mut self, action: &str, asks: &[(&str, &str)], bids: &[(&str, &str)]) -> MockEvent {
+        fn levels(levels: &[(&str, &str)]) -> Value {
+            levels
+                .iter()
+                .map(|(price, size)| json!([price, size, "0", "1"]))
+                .collect()
+        }
+        /// Applies `changes` to a side sorted from the best price
+        fn apply(side: &mut Vec<(String, String)>, changes: &[(&str, &str)], descending: bool) {
+            let key = |price: &str| {
+                let price = price.parse::<f64>().unwrap_or_default();
+                if descending {
+                    -price
+                } else {
+                    price
+                }
+            };
+            for (price, size) in changes {
+                side.retain(|(level, _)| key(level) != key(price));
+                if size.parse::<f64>().unwrap_or_default() > 0.0 {
+                    side.push((price.to_string(), size.to_string()));
+                }
+            }
+            side.sort_by(|(a, _), (b, _)| key(a).total_cmp(&key(b)));
+        }
+        apply(&mut self.asks, asks, false);
+        apply(&mut self.bids, bids, true);
+        let prev_seq_id = self.prev_seq_id;
+        self.seq_id += 1;
+        self.prev_seq_id = self.seq_id;
+        self.ts += 10;
+        MockEvent::Message(
+            json!({
+                "arg": { "channel": self.channel, "instId": self.inst_id },
+                "action": action,
+                "data": [{
+                    "asks": levels(asks),
+                    "bids": levels(bids),
+                    "ts": self.ts.to_string(),
+                    "checksum": self.checksum(),
+                    "prevSeqId": prev_seq_id,
+                    "seqId": self.seq_id,
+                }]
+            })
+            .to_string(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockCredential {
+    key: String,
+    secret: String,
+    passphrase: String,
+}
+
+#[derive(Debug, Default)]
+pub struct MockServerBuilder {
+    credential: Option<MockCredential>,
+    feeds: HashMap<String, Vec<MockEvent>>,
+}
+
+impl MockServerBuilder {
+    /// Credentials accepted by `login`. Without credentials every login is rejected.
+    pub fn credential(mut self, key: &str, secret: &str, passphrase: &str) -> Self {
+        self.credential = Some(MockCredential {
+            key: key.to_string(),
+            secret: secret.to_string(),
+            passphrase: passphrase.to_string(),
+        });
+        self
+    }
+
+    /// Events played on a connection after it subscribes to `channel`.
+    /// Feeds of the same channel are concatenated.
+    pub fn feed(mut self, channel: &str, events: impl IntoIterator<Item = MockEvent>) -> Self {
+        self.feeds
+            .entry(channel.to_string())
+            .or_default()
+            .extend(events);
+        self
+    }
+
+    pub fn bind(self, addr: impl ToSocketAddrs) -> io::Result<MockServer> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(ServerState {
+            credential: self.credential,
+            feeds: self.feeds,
+            connections: Mutex::new(HashMap::new()),
+            next_conn_id: AtomicU64::new(1),
+            shutdown: AtomicBool::new(false),
+        });
+        let accept_state = state.clone();
+        thread::spawn(move || accept(listener, accept_state));
+        Ok(MockServer { addr, state })
+    }
+}
+
+struct ServerState {
+    credential: Option<MockCredential>,
+    feeds: HashMap<String, Vec<MockEvent>>,
+    /// Commands of each open connection by id, removed when the connection ends
+    connections: Mutex<HashMap<u64, Sender<MockEvent>>>,
+    next_conn_id: AtomicU64,
+    shutdown: AtomicBool,
+}
+
+/// Running mock server, stopped when dropped
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<ServerState>,
+}
+
+impl MockServer {
+    pub fn builder() -> MockServerBuilder {
+        MockServerBuilder::default()
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Environment pointing every websocket endpoint at this server
+    pub fn env(&self) -> MockEnv {
+        MockEnv { url: self.url() }
+    }
+
+    /// Plays `event` on every open connection, e.g. to inject a disconnect or an error mid-stream
+    pub fn broadcast(&self, event: MockEvent) {
+        let connections = self.state.connections.lock().unwrap();
+        for connection in connections.values() {
+            // a connection ending concurrently is removed once its thread returns
+            let _ = connection.send(event.clone());
+        }
+    }
+
+    /// Number of connections still open
+    pub fn connection_count(&self) -> usize {
+        self.state.connections.lock().unwrap().len()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.state.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// [`OKXEnv`] of a [`MockServer`], REST requests are not served
+#[derive(Debug, Clone)]
+pub struct MockEnv {
+    url: String,
+}
+
+impl OKXEnv for MockEnv {
+    fn rest(&self) -> &str {
+        &self.url
+    }
+
+    fn public_websocket(&self) -> &str {
+        &self.url
+    }
+
+    fn private_websocket(&self) -> &str {
+        &self.url
+    }
+
+    fn business_websocket(&self) -> &str {
+        &self.url
+    }
+}
+
+fn accept(listener: TcpListener, state: Arc<ServerState>) {
+    while !state.shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (commands, commands_rx) = mpsc::channel();
+                let id = state.next_conn_id.fetch_add(1, Ordering::Relaxed);
+                state.connections.lock().unwrap().insert(id, commands);
+                let state = state.clone();
+                thread::spawn(move || {
+                    if let Err(err) = serve(stream, &state, id, commands_rx) {
+                        log::debug!("mock connection closed: {:?}", err);
+                    }
+                    state.connections.lock().unwrap().remove(&id);
+                });
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(err) => {
+                log::error!("mock server accept failed: {:?}", err);
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    op: String,
+    #[serde(default)]
+    args: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginArg {
+    api_key: String,
+    passphrase: String,
+    timestamp: String,
+    sign: String,
+}
+
+struct Connection<'a> {
+    state: &'a ServerState,
+    conn_id: String,
+    logged_in: bool,
+    played: Vec<String>,
+    queue: VecDeque<MockEvent>,
+    next_at: Instant,
+}
+
+fn serve(
+    stream: TcpStream,
+    state: &ServerState,
+    id: u64,
+    commands: Receiver<MockEvent>,
+) -> tungstenite::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut ws = tungstenite::accept(stream).map_err(|err| match err {
+        tungstenite::HandshakeError::Failure(err) => err,
+        tungstenite::HandshakeError::Interrupted(_) => {
+            tungstenite::Error::Io(ErrorKind::WouldBlock.into())
+        }
+    })?;
+    ws.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let mut connection = Connection {
+        state,
+        conn_id: format!("{:08x}", id),
+        logged_in: false,
+        played: Vec::new(),
+        queue: VecDeque::new(),
+        next_at: Instant::now(),
+    };
+    loop {
+        if state.shutdown.load(Ordering::Relaxed) {
+            return ws.close(None);
+        }
+        match ws.read() {
+            Ok(Message::Text(text)) => connection.handle(&mut ws, &text)?,
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(err) => return Err(err),
+        }
+        connection.queue.extend(commands.try_iter());
+        if !connection.play(&mut ws)? {
+            return Ok(());
+        }
+    }
+}
+
+impl<'a> Connection<'a> {
+    fn handle(&mut self, ws: &mut WebSocket<TcpStream>, text: &str) -> tungstenite::Result<()> {
+        if text == "ping" {
+            return ws.send(Message::Text("pong".into()));
+        }
+        let request: Request = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(_) => return self.send_error(ws, 60012, &format!("Invalid request: {text}")),
+        };
+        match request.op.as_str() {
+            "login" => self.login(ws, &request.args),
+            "subscribe" => {
+                for arg in request.args {
+                    let channel = arg["channel"].as_str().unwrap_or_default().to_string();
+                    if ChannelEvent::requires_login(&channel) && !self.logged_in {
+                        self.send_error(ws, 60011, "Please log in")?;
+                        continue;
+                    }
+                    self.send_event(ws, json!({ "event": "subscribe", "arg": arg }))?;
+                    if !self.played.contains(&channel) {
+                        if let Some(feed) = self.state.feeds.get(&channel) {
+                            self.queue.extend(feed.iter().cloned());
+                        }
+                        self.played.push(channel);
+                    }
+                }
+                Ok(())
+            }
+            "unsubscribe" => {
+                for arg in request.args {
+                    self.send_event(ws, json!({ "event": "unsubscribe", "arg": arg }))?;
+                }
+                Ok(())
+            }
+            op => self.send_error(ws, 60012, &format!("Invalid request: unsupported op {op}")),
+        }
+    }
+
+    fn login(&mut self, ws: &mut WebSocket<TcpStream>, args: &[Value]) -> tungstenite::Result<()> {
+        let arg = match args.first().map(LoginArg::deserialize) {
+            Some(Ok(arg)) => arg,
+            _ => return self.send_error(ws, 60012, "Invalid request: login args"),
+        };
+        let credential = match &self.state.credential {
+            Some(credential) if credential.key == arg.api_key => credential,
+            _ => return self.send_error(ws, 60005, "Invalid apiKey"),
+        };
+        if credential.passphrase != arg.passphrase {
+            return self.send_error(ws, 60024, "Wrong passphrase");
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+        match arg.timestamp.parse::<u64>() {
+            Ok(timestamp) if timestamp.abs_diff(now) <= LOGIN_TIMESTAMP_TOLERANCE => {}
+            _ => return self.send_error(ws, 60004, "Invalid timestamp"),
+        }
+        let (_, sign) = Credential::new(&credential.key, &credential.secret).signature_ws(
+            reqwest::Method::GET,
+            &arg.timestamp,
+            "/users/self/verify",
+        );
+        if sign != arg.sign {
+            return self.send_error(ws, 60007, "Invalid sign");
+        }
+        self.logged_in = true;
+        self.send_event(ws, json!({ "event": "login", "code": "0", "msg": "" }))
+    }
+
+    /// Plays queued events that are due. Returns `false` once the connection should be dropped.
+    fn play(&mut self, ws: &mut WebSocket<TcpStream>) -> tungstenite::Result<bool> {
+        while Instant::now() >= self.next_at {
+            let Some(event) = self.queue.pop_front() else {
+                break;
+            };
+            match event {
+                MockEvent::Message(text) => ws.send(Message::Text(text))?,
+                MockEvent::Delay(delay) => self.next_at = Instant::now() + delay,
+                MockEvent::Error { code, msg } => self.send_error(ws, code, &msg)?,
+                MockEvent::Disconnect => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    fn send_error(
+        &self,
+        ws: &mut WebSocket<TcpStream>,
+        code: u64,
+        msg: &str,
+    ) -> tungstenite::Result<()> {
+        self.send_event(
+            ws,
+            json!({ "event": "error", "code": code.to_string(), "msg": msg }),
+        )
+    }
+
+    fn send_event(
+        &self,
+        ws: &mut WebSocket<TcpStream>,
+        mut event: Value,
+    ) -> tungstenite::Result<()> {
+        event["connId"] = Value::String(self.conn_id.clone());
+        ws.send(Message::Text(event.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests_mock_server {
+    use std::net::TcpStream;
+    use std::time::{Duration, Instant};
+
+    use serde_json::json;
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::{Message, WebSocket};
+
+    use super::{BookFeed, MockEvent, MockServer};
+    use crate::api::error::Error;
+    use crate::api::v5::websocket::{AlgoOrdersChannel, OrdersChannel};
+    use crate::api::v5::ws_convert::{ChannelEvent, ControlEvent, WsMessage};
+    use crate::api::v5::InstrumentType;
+    use crate::api::Options;
+    use crate::websocket::checksum::crc32;
+    use crate::websocket::conn::Books;
+    use crate::websocket::{OKXAuth, WebsocketChannel};
+
+    type Client = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    fn connect(server: &MockServer) -> Client {
+        let (ws, _) = tungstenite::connect(server.url()).unwrap();
+        ws
+    }
+
+    fn read(ws: &mut Client) -> String {
+        loop {
+            if let Message::Text(text) = ws.read().unwrap() {
+                return text;
+            }
+        }
+    }
+
+    fn control(ws: &mut Client) -> ControlEvent {
+        match WsMessage::try_parse(&read(ws)).unwrap() {
+            Some(WsMessage::Control(event)) => event,
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_login_and_private_subscribe() {
+        let server = MockServer::builder()
+            .credential("key", "secret", "passphrase")
+            .feed(
+                OrdersChannel::CHANNEL,
+                [
+                    MockEvent::push(
+                        json!({ "channel": "orders", "instType": "ANY" }),
+                        json!([{ "instType": "SPOT", "instId": "BTC-USDT", "ordId": "1", "state": "live" }]),
+                    ),
+                    MockEvent::Error {
+                        code: 60018,
+                        msg: "injected".into(),
+                    },
+                ],
+            )
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let mut ws = connect(&server);
+        let orders = OrdersChannel(InstrumentType::Any);
+
+        // private channels require login
+        let algo_orders = AlgoOrdersChannel {
+            inst_type: InstrumentType::Any,
+            inst_family: None,
+            inst_id: None,
+        };
+        for message in [orders.subscribe_message(), algo_orders.subscribe_message()] {
+            ws.send(Message::Text(message)).unwrap();
+            assert!(matches!(
+                control(&mut ws),
+                ControlEvent::Error {
+                    code: Some(60011),
+                    ..
+                }
+            ));
+        }
+
+        let wrong = Options::new_with(server.env(), "key", "wrong secret", "passphrase");
+        ws.send(Message::Text(OKXAuth::ws_auth(wrong).unwrap()))
+            .unwrap();
+        assert!(matches!(
+            control(&mut ws),
+            ControlEvent::Error {
+                code: Some(60007),
+                ..
+            }
+        ));
+
+        let options = Options::new_with(server.env(), "key", "secret", "passphrase");
+        ws.send(Message::Text(OKXAuth::ws_auth(options).unwrap()))
+            .unwrap();
+        assert!(matches!(control(&mut ws), ControlEvent::LoginOk { .. }));
+
+        ws.send(Message::Text(orders.subscribe_message())).unwrap();
+        assert!(matches!(
+            control(&mut ws),
+            ControlEvent::SubscribeAck { .. }
+        ));
+        match ChannelEvent::try_parse(&read(&mut ws)).unwrap() {
+            Some(ChannelEvent::Orders(response)) => {
+                assert_eq!(response.data.unwrap()[0].ord_id, Some("1"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            ChannelEvent::try_parse(&read(&mut ws)),
+            Err(Error::Api(_))
+        ));
+    }
+
+    #[test]
+    fn test_book_feed_gap_and_disconnect() {
+        let mut feed = BookFeed::new(Books::CHANNEL, "BTC-USDT");
+        let mut events = vec![
+            feed.snapshot(&[("42000.1", "1")], &[("42000", "2")]),
+            feed.update(&[("42000.1", "0")], &[]),
+        ];
+        feed.gap(3);
+        events.push(feed.update(&[], &[("41999", "1")]));
+        events.push(MockEvent::Delay(Duration::from_millis(20)));
+        events.push(MockEvent::Disconnect);
+
+        let server = MockServer::builder()
+            .feed(Books::CHANNEL, events)
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let mut ws = connect(&server);
+        let books = Books {
+            inst_id: "BTC-USDT".into(),
+        };
+        ws.send(Message::Text(books.subscribe_message())).unwrap();
+        assert!(matches!(
+            control(&mut ws),
+            ControlEvent::SubscribeAck { .. }
+        ));
+        assert_eq!(server.connection_count(), 1);
+
+        let mut seq = Vec::new();
+        let mut checksums = Vec::new();
+        for _ in 0..3 {
+            match ChannelEvent::try_parse(&read(&mut ws)).unwrap() {
+                Some(ChannelEvent::Books(response)) => {
+                    let update = &response.data.unwrap()[0];
+                    seq.push((update.prev_seq_id.unwrap(), update.seq_id.unwrap()));
+                    checksums.push(update.checksum.unwrap());
+                }
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+        assert_eq!(seq, vec![(-1, 1001), (1001, 1002), (1005, 1006)]);
+        assert_eq!(
+            checksums,
+            [&b"42000:2:42000.1:1"[..], b"42000:2", b"42000:2:41999:1"]
+                .map(|book| crc32(book) as i32 as i64)
+        );
+        assert!(ws.read().is_err());
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while server.connection_count() > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(server.connection_count(), 0);
+    }
+}
@@ -1,27 +1,73 @@
 use std::fmt::Debug;
+use std::time::Duration;
 
 use anyhow::bail;
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
 use crate::api::credential::Credential;
 use crate::api::Options;
 
+#[cfg(feature = "websocket")]
+pub mod blocking;
+pub mod capture;
+pub mod checksum;
+#[cfg(feature = "websocket-async")]
+pub mod client;
 pub mod conn;
+pub mod latency;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "websocket-async")]
+pub mod pool;
+pub mod subscription;
+
+/// OKX disconnects when nothing is received for 30 seconds, send `ping` before that.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(25);
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// OKX websocket endpoint serving a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Public,
+    Private,
+    Business,
+}
+
+impl Endpoint {
+    pub fn url(self, options: &Options) -> &str {
+        match self {
+            Endpoint::Public => options.public_websocket(),
+            Endpoint::Private => options.private_websocket(),
+            Endpoint::Business => options.business_websocket(),
+        }
+    }
+}
 
 pub trait WebsocketChannel: Send + Sync {
     const CHANNEL: &'static str;
     const AUTH: bool = false;
+    /// Endpoint the channel is subscribed on. Channels requiring login default to [`Endpoint::Private`]
+    const ENDPOINT: Endpoint = if Self::AUTH {
+        Endpoint::Private
+    } else {
+        Endpoint::Public
+    };
     type Response<'de>: Deserialize<'de> + Debug;
     type ArgType<'de>: Deserialize<'de> + Debug;
 
+    /// The object placed in `args` of a subscribe / unsubscribe request,
+    /// e.g. `{"channel":"books","instId":"BTC-USDT"}`
+    fn subscribe_arg(&self) -> serde_json::Value {
+        serde_json::json!({
+            "channel": Self::CHANNEL,
+        })
+    }
+
     fn subscribe_message(&self) -> String {
         serde_json::json!({
             "op": "subscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                }
-            ]
+            "args": [self.subscribe_arg()]
         })
         .to_string()
     }
@@ -29,17 +75,48 @@ pub trait WebsocketChannel: Send + Sync {
     fn unsubscribe_message(&self) -> String {
         serde_json::json!({
             "op": "unsubscribe",
-            "args": [
-                {
-                    "channel": Self::CHANNEL,
-                }
-            ]
+            "args": [self.subscribe_arg()]
         })
         .to_string()
     }
     fn is_private(&self) -> bool {
         Self::AUTH
     }
+
+    fn endpoint(&self) -> Endpoint {
+        Self::ENDPOINT
+    }
+
+    /// Whether `arg.channel` of a pushed message belongs to this channel.
+    /// Channels named after an interval, e.g. `candle1m`, match on the `CHANNEL` prefix.
+    fn matches_channel(channel: &str) -> bool {
+        channel == Self::CHANNEL
+    }
+}
+
+/// Trading request sent over the private websocket, e.g. `op: order`.
+/// Responses are matched to requests by the client supplied `id`.
+pub trait WebsocketOp {
+    const OP: &'static str;
+    /// Endpoint accepting the op, most trading ops are served on [`Endpoint::Private`]
+    const ENDPOINT: Endpoint = Endpoint::Private;
+    type Arg: Serialize;
+    type Response: DeserializeOwned + Debug;
+
+    fn args(&self) -> &[Self::Arg];
+
+    fn op_message(&self, id: &str) -> String {
+        serde_json::json!({
+            "id": id,
+            "op": Self::OP,
+            "args": self.args(),
+        })
+        .to_string()
+    }
+
+    fn endpoint(&self) -> Endpoint {
+        Self::ENDPOINT
+    }
 }
 
 pub struct OKXAuth;
@@ -0,0 +1,319 @@
+//! Pool of public websocket connections sharing one set of subscriptions.
+//!
+//! Subscriptions are sharded by instrument across the connections with [`Subscriptions::shard`],
+//! and frames of all connections are merged into one stream in order of arrival.
+//! With `redundant` set, every arg is subscribed on two connections and book frames are
+//! deduplicated by `seqId`, so whichever connection delivers an update first wins.
+//!
+//! ```no_run
+//! use okx_rs::api::v5::ws_convert::WsMessage;
+//! use okx_rs::api::Options;
+//! use okx_rs::api::Production;
+//! use okx_rs::websocket::conn::Books;
+//! use okx_rs::websocket::pool::{PoolConfig, WsPool};
+//! use okx_rs::websocket::subscription::Subscriptions;
+//!
+//! # async fn run() {
+//! let subscriptions: Subscriptions = ["BTC-USDT", "ETH-USDT", "SOL-USDT"]
+//!     .into_iter()
+//!     .map(|inst_id| Books { inst_id: inst_id.into() })
+//!     .collect();
+//! let options = Options::new(Production);
+//! let config = PoolConfig { connections: 2, redundant: true };
+//! let (_pool, mut incoming) = WsPool::connect(options.public_websocket(), &subscriptions, config)
+//!     .await
+//!     .unwrap();
+//! while let Some(frame) = incoming.recv().await {
+//!     println!("{} {} {:?}", frame.conn, frame.recv_ts, WsMessage::try_parse(&frame.frame));
+//! }
+//! # }
+//! ```
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use crate::api::error::Error;
+use crate::websocket::client::WsClient;
+use crate::websocket::subscription::Subscriptions;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PoolConfig {
+    /// Number of connections subscriptions are sharded across
+    pub connections: usize,
+    /// Subscribe every arg on two connections and drop book frames already received on the other
+    pub redundant: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            connections: 2,
+            redundant: false,
+        }
+    }
+}
+
+/// Text frame received by a pool connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolFrame {
+    /// Index of the connection in [`WsPool::clients`]
+    pub conn: usize,
+    /// Local receive time in µs since the unix epoch
+    pub recv_ts: u64,
+    pub frame: String,
+}
+
+/// Frames of all pool connections in order of arrival.
+/// The receiver is closed once every connection is closed.
+pub type PoolReceiver = mpsc::UnboundedReceiver<PoolFrame>;
+
+pub struct WsPool {
+    clients: Vec<WsClient>,
+    shards: Vec<Subscriptions>,
+}
+
+impl WsPool {
+    /// Opens `config.connections` connections to `url` and subscribes each to its shard.
+    /// Connections left without args by sharding are not opened.
+    pub async fn connect(
+        url: &str,
+        subscriptions: &Subscriptions,
+        config: PoolConfig,
+    ) -> Result<(Self, PoolReceiver), Error<()>> {
+        let replicas = if config.redundant { 2 } else { 1 };
+        let shards = subscriptions
+            .shard(config.connections, replicas)
+            .into_iter()
+            .filter(|shard| !shard.is_empty())
+            .collect::<Vec<_>>();
+        let dedup = config.redundant.then(SeqDedup::default);
+
+        let (merged, merged_rx) = mpsc::unbounded_channel();
+        let mut clients = Vec::with_capacity(shards.len());
+        for (conn, shard) in shards.iter().enumerate() {
+            let (client, mut incoming) = WsClient::connect_stamped(url).await?;
+            client.subscribe_all(shard)?;
+
+            let merged = merged.clone();
+            let dedup = dedup.clone();
+            tokio::spawn(async move {
+                while let Some(stamped) = incoming.recv().await {
+                    if dedup
+                        .as_ref()
+                        .is_some_and(|dedup| !dedup.accept(conn, &stamped.frame))
+                    {
+                        continue;
+                    }
+                    let frame = PoolFrame {
+                        conn,
+                        recv_ts: stamped.recv_ts,
+                        frame: stamped.frame,
+                    };
+                    if merged.send(frame).is_err() {
+                        break;
+                    }
+                }
+            });
+            clients.push(client);
+        }
+        Ok((Self { clients, shards }, merged_rx))
+    }
+
+    #[inline]
+    pub fn clients(&self) -> &[WsClient] {
+        &self.clients
+    }
+
+    /// Subscriptions of each connection, by index in [`WsPool::clients`]
+    #[inline]
+    pub fn shards(&self) -> &[Subscriptions] {
+        &self.shards
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BookHeader<'a> {
+    #[serde(borrow)]
+    arg: Option<BookHeaderArg<'a>>,
+    #[serde(borrow)]
+    action: Option<Cow<'a, str>>,
+    #[serde(default)]
+    data: Vec<BookHeaderData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookHeaderArg<'a> {
+    #[serde(borrow)]
+    channel: Cow<'a, str>,
+    #[serde(borrow)]
+    inst_id: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookHeaderData {
+    seq_id: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct SeqState {
+    last_seq_id: i64,
+    /// Connections that delivered a snapshot of the book
+    snapshots: Vec<usize>,
+}
+
+/// Last `seqId` delivered per channel and instrument, shared by the pool connections
+#[derive(Debug, Clone, Default)]
+struct SeqDedup(Arc<Mutex<HashMap<(String, String), SeqState>>>);
+
+impl SeqDedup {
+    /// Whether `frame` received on connection `conn` is new. Frames without `seqId` always are.
+    ///
+    /// An update is new if its `seqId` is past the last one delivered, as is a snapshot.
+    /// A snapshot behind the last `seqId` is a sequence reset when `conn` already sent a snapshot
+    /// of the book, and otherwise the late initial snapshot of a replica.
+    fn accept(&self, conn: usize, frame: &str) -> bool {
+        if !frame.contains(r#""seqId""#) {
+            return true;
+        }
+        let header = match serde_json::from_str::<BookHeader>(frame) {
+            Ok(header) => header,
+            Err(_) => return true,
+        };
+        let (Some(arg), Some(seq_id)) =
+            (header.arg, header.data.first().and_then(|data| data.seq_id))
+        else {
+            return true;
+        };
+        let key = (
+            arg.channel.into_owned(),
+            arg.inst_id.unwrap_or_default().into_owned(),
+        );
+        let snapshot = header.action.as_deref() == Some("snapshot");
+        let mut states = self.0.lock().unwrap();
+        let Some(state) = states.get_mut(&key) else {
+            let snapshots = if snapshot { vec![conn] } else { vec![] };
+            states.insert(
+                key,
+                SeqState {
+                    last_seq_id: seq_id,
+                    snapshots,
+                },
+            );
+            return true;
+        };
+        let reset = if snapshot {
+            let resnapshot = state.snapshots.contains(&conn);
+            if !resnapshot {
+                state.snapshots.push(conn);
+            }
+            resnapshot && seq_id < state.last_seq_id
+        } else {
+            false
+        };
+        if reset {
+            state.snapshots = vec![conn];
+        } else if seq_id <= state.last_seq_id {
+            return false;
+        }
+        state.last_seq_id = seq_id;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests_pool {
+    use super::SeqDedup;
+
+    fn book(action: &str, inst_id: &str, seq_id: i64) -> String {
+        format!(
+            r#"{{"arg":{{"channel":"books","instId":"{inst_id}"}},"action":"{action}","data":[{{"asks":[],"bids":[],"ts":"1","checksum":0,"prevSeqId":{},"seqId":{seq_id}}}]}}"#,
+            seq_id - 1
+        )
+    }
+
+    #[test]
+    fn test_seq_dedup() {
+        let dedup = SeqDedup::default();
+        assert!(dedup.accept(0, &book("snapshot", "BTC-USDT", 10)));
+        assert!(!dedup.accept(1, &book("snapshot", "BTC-USDT", 10)));
+        assert!(dedup.accept(1, &book("snapshot", "ETH-USDT", 10)));
+        assert!(dedup.accept(0, &book("update", "BTC-USDT", 11)));
+        assert!(dedup.accept(1, &book("update", "BTC-USDT", 13)));
+        assert!(!dedup.accept(0, &book("update", "BTC-USDT", 11)));
+        assert!(!dedup.accept(0, &book("update", "BTC-USDT", 12)));
+        // late initial snapshot of a replica
+        assert!(!dedup.accept(2, &book("snapshot", "BTC-USDT", 12)));
+        assert!(!dedup.accept(2, &book("update", "BTC-USDT", 13)));
+        // sequence reset, snapshots again on every connection
+        assert!(dedup.accept(0, &book("snapshot", "BTC-USDT", 2)));
+        assert!(!dedup.accept(1, &book("snapshot", "BTC-USDT", 2)));
+        assert!(dedup.accept(1, &book("update", "BTC-USDT", 3)));
+        assert!(!dedup.accept(0, &book("update", "BTC-USDT", 3)));
+        assert!(dedup.accept(0, r#"{"event":"subscribe","arg":{"channel":"books"}}"#));
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_redundant_pool() {
+        use std::time::Duration;
+
+        use super::{PoolConfig, WsPool};
+        use crate::api::v5::ws_convert::{ChannelEvent, WsMessage};
+        use crate::websocket::conn::Books;
+        use crate::websocket::mock::{BookFeed, MockServer};
+        use crate::websocket::subscription::Subscriptions;
+        use crate::websocket::WebsocketChannel;
+
+        let mut feed = BookFeed::new(Books::CHANNEL, "BTC-USDT");
+        let mut events = vec![feed.snapshot(&[("101", "1")], &[("99", "1")])];
+        for _ in 0..20 {
+            events.push(feed.update(&[("101", "2")], &[]));
+        }
+        let last_seq_id = feed.seq_id();
+        let server = MockServer::builder()
+            .feed(Books::CHANNEL, events)
+            .bind("127.0.0.1:0")
+            .unwrap();
+
+        let subscriptions: Subscriptions = ["BTC-USDT", "ETH-USDT", "SOL-USDT"]
+            .into_iter()
+            .map(|inst_id| Books {
+                inst_id: inst_id.into(),
+            })
+            .collect();
+        let config = PoolConfig {
+            connections: 3,
+            redundant: true,
+        };
+        let (pool, mut incoming) = WsPool::connect(&server.url(), &subscriptions, config)
+            .await
+            .unwrap();
+        assert_eq!(pool.clients().len(), pool.shards().len());
+        // every book is subscribed on two connections
+        assert_eq!(
+            pool.shards().iter().map(Subscriptions::len).sum::<usize>(),
+            6
+        );
+
+        let mut seq_ids = vec![];
+        while let Ok(Some(frame)) =
+            tokio::time::timeout(Duration::from_millis(300), incoming.recv()).await
+        {
+            if let Ok(Some(WsMessage::Channel(ChannelEvent::Books(response)))) =
+                WsMessage::try_parse(&frame.frame)
+            {
+                seq_ids.push(response.data.unwrap()[0].seq_id.unwrap());
+            }
+        }
+        // the feed plays on both connections subscribed to BTC-USDT, each update is delivered once
+        assert_eq!(
+            seq_ids,
+            (last_seq_id - 20..=last_seq_id).collect::<Vec<_>>()
+        );
+    }
+}
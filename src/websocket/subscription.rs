@@ -0,0 +1,390 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::websocket::{Endpoint, WebsocketChannel};
+
+/// Client side limits used when packing subscription args into frames.
+#[derive(Debug, Clone, Copy)]
+pub struct SubscriptionLimits {
+    /// Maximum length in bytes of a single subscribe / unsubscribe frame.
+    /// OKX rejects requests whose total args length exceeds 64 KB.
+    pub max_frame_bytes: usize,
+    /// Maximum number of args kept on a single connection before a new one is used.
+    /// OKX does not publish a hard cap, but heavily subscribed connections are throttled
+    /// and slow to push, so large sets are spread across connections.
+    pub max_args_per_connection: usize,
+}
+
+impl Default for SubscriptionLimits {
+    fn default() -> Self {
+        Self {
+            max_frame_bytes: 64 * 1024,
+            max_args_per_connection: 100,
+        }
+    }
+}
+
+/// A set of channel subscriptions that are sent as a few batched `subscribe` frames
+/// instead of one frame per channel.
+///
+/// Each arg remembers the [`Endpoint`] of its channel and whether it requires login,
+/// [`Subscriptions::connections`] never mixes endpoints on a connection.
+///
+/// ```
+/// use okx_rs::websocket::conn::Books5;
+/// use okx_rs::websocket::subscription::Subscriptions;
+///
+/// let mut subscriptions = Subscriptions::default();
+/// for inst_id in ["BTC-USDT", "ETH-USDT"] {
+///     subscriptions.add(&Books5 {
+///         inst_id: inst_id.into(),
+///     });
+/// }
+/// assert_eq!(subscriptions.subscribe_messages().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Subscriptions {
+    args: Vec<Value>,
+    routes: Vec<ArgRoute>,
+    /// Serialized args, to ignore duplicates without scanning `args`
+    keys: HashSet<String>,
+    limits: SubscriptionLimits,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ArgRoute {
+    endpoint: Endpoint,
+    auth: bool,
+}
+
+impl Subscriptions {
+    pub fn with_limits(limits: SubscriptionLimits) -> Self {
+        Self {
+            args: Vec::new(),
+            routes: Vec::new(),
+            keys: HashSet::new(),
+            limits,
+        }
+    }
+
+    pub fn add<C: WebsocketChannel>(&mut self, channel: &C) -> &mut Self {
+        self.push_arg_to(C::ENDPOINT, C::AUTH, channel.subscribe_arg())
+    }
+
+    /// Adds a raw subscription arg of a public channel, e.g. `{"channel":"books","instId":"BTC-USDT"}`.
+    /// Duplicated args are ignored.
+    pub fn push_arg(&mut self, arg: Value) -> &mut Self {
+        self.push_arg_to(Endpoint::Public, false, arg)
+    }
+
+    /// Adds a raw subscription arg of a channel served on `endpoint`.
+    /// Duplicated args are ignored.
+    pub fn push_arg_to(&mut self, endpoint: Endpoint, auth: bool, arg: Value) -> &mut Self {
+        self.push(arg, ArgRoute { endpoint, auth });
+        self
+    }
+
+    fn push(&mut self, arg: Value, route: ArgRoute) {
+        if self.keys.insert(arg.to_string()) {
+            self.args.push(arg);
+            self.routes.push(route);
+        }
+    }
+
+    /// Endpoint shared by all args, `None` if empty or spanning several endpoints
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        let endpoint = self.routes.first()?.endpoint;
+        self.routes
+            .iter()
+            .all(|route| route.endpoint == endpoint)
+            .then_some(endpoint)
+    }
+
+    /// Whether any of the channels requires login
+    pub fn requires_login(&self) -> bool {
+        self.routes.iter().any(|route| route.auth)
+    }
+
+    #[inline]
+    pub fn args(&self) -> &[Value] {
+        &self.args
+    }
+
+    #[inline]
+    pub fn limits(&self) -> SubscriptionLimits {
+        self.limits
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// `op: subscribe` frames covering all args, each within `max_frame_bytes`.
+    pub fn subscribe_messages(&self) -> Vec<String> {
+        batch_messages("subscribe", &self.args, self.limits.max_frame_bytes)
+    }
+
+    /// `op: unsubscribe` frames covering all args, each within `max_frame_bytes`.
+    pub fn unsubscribe_messages(&self) -> Vec<String> {
+        batch_messages("unsubscribe", &self.args, self.limits.max_frame_bytes)
+    }
+
+    /// Splits the set into one `Subscriptions` per connection, each holding args of a single
+    /// [`Endpoint`] and at most `max_args_per_connection` args.
+    pub fn connections(&self) -> Vec<Subscriptions> {
+        let mut endpoints: Vec<Endpoint> = Vec::new();
+        for route in &self.routes {
+            if !endpoints.contains(&route.endpoint) {
+                endpoints.push(route.endpoint);
+            }
+        }
+
+        let max_args = self.limits.max_args_per_connection.max(1);
+        let mut connections = Vec::new();
+        for endpoint in endpoints {
+            let mut connection = Subscriptions::with_limits(self.limits);
+            for (arg, route) in self.args.iter().zip(&self.routes) {
+                if route.endpoint != endpoint {
+                    continue;
+                }
+                if connection.len() == max_args {
+                    connections.push(std::mem::replace(
+                        &mut connection,
+                        Subscriptions::with_limits(self.limits),
+                    ));
+                }
+                connection.push(arg.clone(), *route);
+            }
+            connections.push(connection);
+        }
+        connections
+    }
+
+    /// Spreads args over `shards` sets by instrument (`instId`, else `instFamily`, else `instType`),
+    /// so all channels of an instrument share a connection. Each arg is placed on `replicas`
+    /// consecutive shards, e.g. `2` to receive every book on two connections.
+    ///
+    /// The placement only depends on the arg, not on insertion order or process.
+    pub fn shard(&self, shards: usize, replicas: usize) -> Vec<Subscriptions> {
+        let shards = shards.max(1);
+        let replicas = replicas.clamp(1, shards);
+        let mut sets = vec![Subscriptions::with_limits(self.limits); shards];
+        for (arg, route) in self.args.iter().zip(&self.routes) {
+            let key = ["instId", "instFamily", "instType", "channel"]
+                .iter()
+                .find_map(|field| arg[field].as_str())
+                .unwrap_or_default();
+            let first = (fnv1a(key) % shards as u64) as usize;
+            for replica in 0..replicas {
+                sets[(first + replica) % shards].push(arg.clone(), *route);
+            }
+        }
+        sets
+    }
+}
+
+// stable across processes, unlike `DefaultHasher`
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl<C: WebsocketChannel> Extend<C> for Subscriptions {
+    fn extend<T: IntoIterator<Item = C>>(&mut self, iter: T) {
+        for channel in iter {
+            self.add(&channel);
+        }
+    }
+}
+
+impl<C: WebsocketChannel> FromIterator<C> for Subscriptions {
+    fn from_iter<T: IntoIterator<Item = C>>(iter: T) -> Self {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.extend(iter);
+        subscriptions
+    }
+}
+
+/// Greedily packs args into frames of `{"op":<op>,"args":[...]}`.
+/// An arg that alone exceeds `max_frame_bytes` is still sent in its own frame.
+fn batch_messages(op: &str, args: &[Value], max_frame_bytes: usize) -> Vec<String> {
+    let prefix = format!(r#"{{"op":"{op}","args":["#);
+    let suffix = "]}";
+
+    let mut messages = Vec::new();
+    let mut frame = prefix.clone();
+    let mut count = 0;
+    for arg in args {
+        let arg = arg.to_string();
+        let separator = usize::from(count > 0);
+        if count > 0 && frame.len() + separator + arg.len() + suffix.len() > max_frame_bytes {
+            frame.push_str(suffix);
+            messages.push(std::mem::replace(&mut frame, prefix.clone()));
+            count = 0;
+        }
+        if count > 0 {
+            frame.push(',');
+        }
+        frame.push_str(&arg);
+        count += 1;
+    }
+    if count > 0 {
+        frame.push_str(suffix);
+        messages.push(frame);
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests_subscriptions {
+    use super::{SubscriptionLimits, Subscriptions};
+    use crate::api::v5::websocket::OrdersChannel;
+    use crate::api::v5::{Candles, InstrumentType};
+    use crate::websocket::conn::{Books, Books5};
+    use crate::websocket::{Endpoint, WebsocketChannel};
+
+    fn books(n: usize) -> impl Iterator<Item = Books> {
+        (0..n).map(|i| Books {
+            inst_id: format!("INST{i}-USDT"),
+        })
+    }
+
+    #[test]
+    fn test_single_frame() {
+        let subscriptions: Subscriptions = books(3).collect();
+        let messages = subscriptions.subscribe_messages();
+        assert_eq!(messages.len(), 1);
+
+        let frame: serde_json::Value = serde_json::from_str(&messages[0]).unwrap();
+        assert_eq!(frame["op"], "subscribe");
+        assert_eq!(frame["args"].as_array().unwrap().len(), 3);
+        assert_eq!(frame["args"][2]["instId"], "INST2-USDT");
+        assert_eq!(frame["args"][2]["channel"], Books::CHANNEL);
+    }
+
+    #[test]
+    fn test_chunked_by_frame_size() {
+        let mut subscriptions = Subscriptions::with_limits(SubscriptionLimits {
+            max_frame_bytes: 256,
+            max_args_per_connection: 1000,
+        });
+        subscriptions.extend(books(300));
+
+        let messages = subscriptions.unsubscribe_messages();
+        assert!(messages.len() > 1);
+        let mut total = 0;
+        for message in messages {
+            assert!(message.len() <= 256);
+            let frame: serde_json::Value = serde_json::from_str(&message).unwrap();
+            assert_eq!(frame["op"], "unsubscribe");
+            total += frame["args"].as_array().unwrap().len();
+        }
+        assert_eq!(total, 300);
+    }
+
+    #[test]
+    fn test_split_connections() {
+        let mut subscriptions = Subscriptions::with_limits(SubscriptionLimits {
+            max_frame_bytes: 64 * 1024,
+            max_args_per_connection: 120,
+        });
+        subscriptions.extend(books(300));
+        // duplicates are ignored
+        subscriptions.extend(books(10));
+        assert_eq!(subscriptions.len(), 300);
+
+        let connections = subscriptions.connections();
+        assert_eq!(
+            connections
+                .iter()
+                .map(Subscriptions::len)
+                .collect::<Vec<_>>(),
+            vec![120, 120, 60]
+        );
+    }
+
+    #[test]
+    fn test_shard_by_instrument() {
+        let mut subscriptions: Subscriptions = books(100).collect();
+        subscriptions.extend((0..100).map(|i| Books5 {
+            inst_id: format!("INST{i}-USDT"),
+        }));
+
+        let shards = subscriptions.shard(4, 1);
+        assert_eq!(shards.iter().map(Subscriptions::len).sum::<usize>(), 200);
+        for shard in &shards {
+            assert!(!shard.is_empty());
+            // both channels of an instrument land on the same shard
+            for arg in shard.args() {
+                assert_eq!(
+                    shard
+                        .args()
+                        .iter()
+                        .filter(|other| other["instId"] == arg["instId"])
+                        .count(),
+                    2
+                );
+            }
+        }
+        assert_eq!(shards[2].args(), subscriptions.shard(4, 1)[2].args());
+
+        let replicated = subscriptions.shard(4, 2);
+        assert_eq!(
+            replicated.iter().map(Subscriptions::len).sum::<usize>(),
+            400
+        );
+        for (i, shard) in shards.iter().enumerate() {
+            for arg in shard.args() {
+                assert!(replicated[i].args().contains(arg));
+                assert!(replicated[(i + 1) % 4].args().contains(arg));
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_by_endpoint() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.add(&Books {
+            inst_id: "BTC-USDT".into(),
+        });
+        subscriptions.add(&Candles {
+            bar: "1m".into(),
+            inst_id: "BTC-USDT".into(),
+        });
+        subscriptions.add(&OrdersChannel(InstrumentType::Any));
+        subscriptions.add(&Books {
+            inst_id: "ETH-USDT".into(),
+        });
+        assert_eq!(subscriptions.endpoint(), None);
+        assert!(subscriptions.requires_login());
+
+        let connections = subscriptions.connections();
+        let summary = connections
+            .iter()
+            .map(|connection| {
+                (
+                    connection.endpoint(),
+                    connection.requires_login(),
+                    connection.len(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (Some(Endpoint::Public), false, 2),
+                (Some(Endpoint::Business), false, 1),
+                (Some(Endpoint::Private), true, 1),
+            ]
+        );
+        assert_eq!(connections[1].args()[0]["channel"], "candle1m");
+    }
+}